@@ -1,5 +1,9 @@
-use hanabi::{Clue, HanabiGame, Player};
-use hanabi::cards::{Rank, Suit};
+use hanabi::{Clue, ClueKind, GameConfig, HanabiGame, Player, StackDirection};
+use hanabi::cards::{Parity, Rank, Suit};
+use hanabi::events::GameEvent;
+use hanabi::registry::{Registry, VariantDefinition};
+use hanabi::stats::Efficiency;
+use hanabi::view::CardView;
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -23,12 +27,11 @@ struct App {
 }
 
 impl App {
-    fn new() -> App {
-        let variant = hanabi::variants::NoVariant;
+    fn new(variant: &'static VariantDefinition) -> App {
         let game = HanabiGame::new(vec![
             Player::new("Alice".to_string()),
             Player::new("Bob".to_string()),
-        ], variant);
+        ], variant, GameConfig::default()).expect("two players can always be dealt");
         
         App {
             game,
@@ -50,6 +53,19 @@ impl App {
                 self.show_help = !self.show_help;
                 return false;
             }
+            "undo" => {
+                if !self.game.undo() {
+                    self.messages.push("Nothing to undo".to_string());
+                }
+            }
+            "redo" => match self.game.redo() {
+                Some(events) => {
+                    let events = self.game.public_events(&events);
+                    let messages: Vec<String> = events.iter().filter_map(|event| self.describe(event)).collect();
+                    self.messages.extend(messages);
+                }
+                None => self.messages.push("Nothing to redo".to_string()),
+            },
             "play" => {
                 if parts.len() != 2 {
                     self.messages.push("Usage: play <card_index>".to_string());
@@ -57,14 +73,7 @@ impl App {
                 }
                 match parts[1].parse::<usize>() {
                     Ok(index) => {
-                        let action = hanabi::AnnotatedAction {
-                            player: self.game.current_player(),
-                            action: hanabi::Action::Play(index),
-                        };
-                        match self.game.act(action) {
-                            Ok(()) => self.messages.push("Card played successfully!".to_string()),
-                            Err(e) => self.messages.push(format!("Error: {:?}", e)),
-                        }
+                        self.act(hanabi::Action::Play(index));
                     }
                     Err(_) => self.messages.push("Invalid card index".to_string()),
                 }
@@ -76,36 +85,21 @@ impl App {
                 }
                 match parts[1].parse::<usize>() {
                     Ok(index) => {
-                        let action = hanabi::AnnotatedAction {
-                            player: self.game.current_player(),
-                            action: hanabi::Action::Discard(index),
-                        };
-                        match self.game.act(action) {
-                            Ok(()) => self.messages.push("Card discarded successfully!".to_string()),
-                            Err(e) => self.messages.push(format!("Error: {:?}", e)),
-                        }
+                        self.act(hanabi::Action::Discard(index));
                     }
                     Err(_) => self.messages.push("Invalid card index".to_string()),
                 }
             }
             "rank" => {
                 if parts.len() != 3 {
-                    self.messages.push("Usage: rank <rank> <player_index>".to_string());
+                    self.messages.push("Usage: rank <rank|odd|even> <player_index>".to_string());
                     return false;
                 }
-                match (Rank::try_from(parts[1]), parts[2].parse::<usize>()) {
-                    (Ok(rank), Ok(target)) => {
-                        let action = hanabi::AnnotatedAction {
-                            player: self.game.current_player(),
-                            action: hanabi::Action::Clue {
-                                clue: Clue::Rank(rank),
-                                target,
-                            },
-                        };
-                        match self.game.act(action) {
-                            Ok(()) => self.messages.push("Rank clue given successfully!".to_string()),
-                            Err(e) => self.messages.push(format!("Error: {:?}", e)),
-                        }
+                let clue = Rank::try_from(parts[1]).map(Clue::Rank)
+                    .or_else(|_| Parity::try_from(parts[1]).map(Clue::Parity));
+                match (clue, parts[2].parse::<usize>()) {
+                    (Ok(clue), Ok(target)) => {
+                        self.act(hanabi::Action::Clue { clue, target });
                     }
                     _ => self.messages.push("Invalid rank or player index".to_string()),
                 }
@@ -117,17 +111,10 @@ impl App {
                 }
                 match (Suit::try_from(parts[1]), parts[2].parse::<usize>()) {
                     (Ok(suit), Ok(target)) => {
-                        let action = hanabi::AnnotatedAction {
-                            player: self.game.current_player(),
-                            action: hanabi::Action::Clue {
-                                clue: Clue::Suit(suit),
-                                target,
-                            },
-                        };
-                        match self.game.act(action) {
-                            Ok(()) => self.messages.push("Suit clue given successfully!".to_string()),
-                            Err(e) => self.messages.push(format!("Error: {:?}", e)),
-                        }
+                        self.act(hanabi::Action::Clue {
+                            clue: Clue::Suit(suit),
+                            target,
+                        });
                     }
                     _ => self.messages.push("Invalid suit or player index".to_string()),
                 }
@@ -138,12 +125,51 @@ impl App {
         }
 
         if self.messages.len() > 10 {
-            self.messages.remove(0);
+            self.messages.drain(..self.messages.len() - 10);
         }
 
         false
     }
 
+    /// Take an action as the current player and log what happened.
+    fn act(&mut self, action: hanabi::Action) {
+        let action = hanabi::AnnotatedAction {
+            player: self.game.current_player(),
+            action,
+        };
+        let max_score = self.game.max_score();
+        match self.game.act(action) {
+            Ok(events) => {
+                let events = self.game.public_events(&events);
+                let messages: Vec<String> = events.iter().filter_map(|event| self.describe(event)).collect();
+                self.messages.extend(messages);
+                if !self.game.concealed() && self.game.max_score() < max_score {
+                    self.messages.push(format!("Warning: the max score is now {}", self.game.max_score()));
+                }
+            }
+            Err(e) => self.messages.push(format!("Error: {:?}", e)),
+        }
+    }
+
+    /// Describe a game event for the message log, if it is worth mentioning.
+    fn describe(&self, event: &GameEvent) -> Option<String> {
+        let name = |player: &usize| &self.game.players()[*player].name;
+        match event {
+            GameEvent::Clue { giver, target, clue, list, .. } => {
+                Some(format!("{} clued {} {}, touching {} card(s)", name(giver), name(target), Self::clue_text(*clue), list.len()))
+            }
+            GameEvent::Play { player, card, .. } => Some(format!("{} played {}", name(player), card)),
+            GameEvent::HiddenPlay { player, .. } => Some(format!("{} threw a card in the hole", name(player))),
+            GameEvent::Discard { player, card, failed: false, .. } => Some(format!("{} discarded {}", name(player), card)),
+            GameEvent::Discard { player, card, failed: true, .. } => Some(format!("{} misplayed {}", name(player), card)),
+            GameEvent::Strike { num, .. } => Some(format!("Strike {}!", num)),
+            GameEvent::GameOver { end_condition } => {
+                Some(format!("Game over ({:?}). Final score: {}", end_condition, self.game.score()))
+            }
+            _ => None,
+        }
+    }
+
     fn suit_color(suit: &Suit) -> Color {
         match suit {
             Suit::Red => Color::Red,
@@ -154,18 +180,79 @@ impl App {
             Suit::Rainbow => Color::White,
             Suit::Black => Color::DarkGray,
             Suit::Pink => Color::LightMagenta,
+            Suit::Teal => Color::Cyan,
+            Suit::Brown => Color::Rgb(150, 90, 40),
+            Suit::LightPink => Color::Rgb(255, 182, 193),
+            Suit::CocoaRainbow => Color::Rgb(120, 70, 50),
+            Suit::White => Color::Gray,
+            Suit::Omni => Color::LightYellow,
+            Suit::Null => Color::Rgb(90, 90, 90),
+            Suit::MuddyRainbow => Color::Rgb(160, 120, 80),
+            Suit::PurpleReversed => Color::Magenta,
+            Suit::TealReversed => Color::Cyan,
+            Suit::OrangeD => Color::Rgb(255, 165, 0),
+            Suit::PurpleD => Color::Rgb(128, 0, 128),
+            Suit::GreenD => Color::Rgb(0, 128, 0),
+            Suit::Tomato => Color::Rgb(255, 99, 71),
+            Suit::Mahogany => Color::Rgb(192, 64, 0),
+            Suit::Sky => Color::Rgb(135, 206, 235),
+            Suit::Navy => Color::Rgb(0, 0, 128),
+            Suit::Lime => Color::Rgb(50, 205, 50),
+            Suit::Forest => Color::Rgb(34, 139, 34),
         }
     }
+
+    /// Show an efficiency to two decimal places, or "-" if no clues are involved.
+    fn efficiency_text(efficiency: Option<Efficiency>) -> String {
+        match efficiency {
+            Some(efficiency) => efficiency.value().map(|value| format!("{:.2}", value)).unwrap_or_else(|| "-".to_string()),
+            None => "?".to_string(),
+        }
+    }
+
+    /// Show a number from the view, or "?" if it is hidden.
+    fn hidden_text<T: std::fmt::Display>(value: Option<T>) -> String {
+        value.map(|value| value.to_string()).unwrap_or_else(|| "?".to_string())
+    }
+
+    /// Describe a clue as the players saw it.
+    fn clue_text(clue: Clue) -> String {
+        match clue {
+            Clue::Suit(suit) => suit.to_string(),
+            Clue::Rank(rank) => rank.to_string(),
+            Clue::Parity(parity) => parity.to_string(),
+            Clue::Hidden(Some(ClueKind::Color)) => "a color".to_string(),
+            Clue::Hidden(Some(ClueKind::Rank)) => "a rank".to_string(),
+            Clue::Hidden(None) => "something".to_string(),
+        }
+    }
+
+    /// Show a card's identity if it is visible, or what its clues have revealed otherwise.
+    fn card_text(card: &CardView) -> String {
+        if let Some(card) = card.card {
+            return card.to_string();
+        }
+        if card.clues.is_empty() {
+            return if card.touched { "(??)" } else { "??" }.to_string();
+        }
+        let colors: String = card.possible_colors.iter().map(String::from).collect();
+        let values: String = card.possible_values.iter().map(|rank| rank.to_string()).collect();
+        format!("({}|{})", colors, values)
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The variant can be given by its "Hanab Live" name, e.g. `hanabi-tui "White (6 Suits)"`.
+    let name = std::env::args().nth(1).unwrap_or_else(|| "No Variant".to_string());
+    let variant = Registry::bundled().by_name(&name).ok_or_else(|| format!("unknown variant {name:?}"))?;
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(variant);
     let res = run_app(&mut terminal, &mut app);
 
     disable_raw_mode()?;
@@ -247,30 +334,46 @@ fn ui(f: &mut Frame, app: &App) {
         .constraints([Constraint::Length(8), Constraint::Min(5)])
         .split(game_area[0]);
 
-    let score_text = format!("Score: {}", app.game.score());
+    // Hot-seat play: show the game from the point of view of whoever's turn it is.
+    let view = app.game.view(app.game.current_player());
+
+    let score_text = vec![
+        Line::from(format!("Score: {} / {}", App::hidden_text(view.score), App::hidden_text(view.max_score))),
+        Line::from(format!("Clues: {}{}", view.clues, if view.half_clue { ".5" } else { "" })),
+        Line::from(format!("Pace: {}", App::hidden_text(view.pace))),
+        Line::from(format!("Efficiency: {} / {}", App::efficiency_text(view.efficiency), App::efficiency_text(view.required_efficiency))),
+        Line::from(format!("Bombs left: {}", App::hidden_text(view.bombs_left))),
+        Line::from(format!("Deck: {}", view.deck_size)),
+    ];
     let score = Paragraph::new(score_text)
         .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
         .block(Block::default().borders(Borders::ALL).title("Game Status"));
     f.render_widget(score, left_area[0]);
 
     let mut stack_lines = Vec::new();
-    for (suit, cards) in app.game.stacks() {
+    for suit in &view.suits {
         let suit_color = App::suit_color(suit);
-        let stack_text = if cards.is_empty() {
-            format!("{:?}: Empty", suit)
-        } else {
-            format!("{:?}: {}", suit, cards.len())
+        let direction = match view.directions.get(suit) {
+            Some(StackDirection::Down) => " (down)",
+            _ => "",
+        };
+        let stack_text = match view.stacks.get(suit) {
+            Some(cards) if !cards.is_empty() => format!("{:?}: {}{}", suit, cards.len(), direction),
+            _ => format!("{:?}: Empty", suit),
         };
         stack_lines.push(Line::from(Span::styled(stack_text, Style::default().fg(suit_color))));
     }
+    if !view.hole.is_empty() {
+        stack_lines.push(Line::from(format!("In the hole: {} card(s)", view.hole.len())));
+    }
     let stacks = Paragraph::new(stack_lines)
         .block(Block::default().borders(Borders::ALL).title("Stacks"))
         .wrap(Wrap { trim: true });
     f.render_widget(stacks, left_area[1]);
 
     let mut player_lines = Vec::new();
-    for (i, player) in app.game.players().iter().enumerate() {
-        let is_current = i == app.game.current_player();
+    for (i, (name, hand)) in view.names.iter().zip(&view.hands).enumerate() {
+        let is_current = i == view.current_player;
         let player_style = if is_current {
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
         } else {
@@ -279,12 +382,12 @@ fn ui(f: &mut Frame, app: &App) {
         
         let prefix = if is_current { "► " } else { "  " };
         player_lines.push(Line::from(Span::styled(
-            format!("{}Player {}: {}", prefix, i, player.name),
+            format!("{}Player {}: {}", prefix, i, name),
             player_style,
         )));
         
-        let hand_text: Vec<String> = player.hand.iter().enumerate()
-            .map(|(idx, card)| format!("[{}] {}", idx, card.card()))
+        let hand_text: Vec<String> = hand.iter().enumerate()
+            .map(|(idx, card)| format!("[{}] {}", idx, App::card_text(card)))
             .collect();
         player_lines.push(Line::from(Span::styled(
             format!("    {}", hand_text.join(" ")),
@@ -321,8 +424,9 @@ fn ui(f: &mut Frame, app: &App) {
             Line::from(""),
             Line::from("play <index>     - Play card at index"),
             Line::from("discard <index>  - Discard card at index"),
-            Line::from("rank <rank> <player> - Give rank clue"),
+            Line::from("rank <rank> <player> - Give rank clue (or odd/even)"),
             Line::from("suit <suit> <player> - Give suit clue"),
+            Line::from("undo / redo      - Step back or forward a turn"),
             Line::from("help / h         - Toggle this help"),
             Line::from("exit / quit / q  - Exit game"),
             Line::from(""),
@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use dioxus::prelude::*;
-use hanabi::{Action, AnnotatedAction, Clue, HanabiGame, Player};
+use hanabi::{Action, AnnotatedAction, Clue, GameConfig, HanabiGame, Player};
 use hanabi::variants::NoVariant;
 use std::sync::{Arc, RwLock};
 
@@ -22,7 +22,7 @@ fn main() {
     let game = HanabiGame::new(vec![
         Player::new("njha".to_string()),
         Player::new("etw".to_string()),
-    ], NoVariant::new());
+    ], NoVariant::new(), GameConfig::default()).expect("two players can always be dealt");
     let game_ref = Arc::new(RwLock::new(game));
 
     let vdom_factory = move || {
@@ -62,6 +62,7 @@ fn App() -> Element {
 #[component]
 fn Player(name: String) -> Element {
     let mut count = use_signal(|| 0);
+    let mut error = use_signal(|| None::<String>);
     let game = use_context::<Arc<RwLock<HanabiGame>>>();
     let my_id = game
         .read()
@@ -70,29 +71,32 @@ fn Player(name: String) -> Element {
         .iter()
         .position(|p| p.name == name)
         .unwrap_or_default();
-    let players: Vec<Player> = game
-        .read()
-        .unwrap()
-        .players()
-        .to_vec();
+    let view = game.read().unwrap().view(my_id);
+    let stacks: Vec<String> = view.suits.iter()
+        .map(|suit| format!("{:?}: {}", suit, view.stacks.get(suit).map(Vec::len).unwrap_or(0)))
+        .collect();
+    let score = view.score.map(|score| score.to_string()).unwrap_or_else(|| "hidden".to_string());
 
     rsx! {
         h1 { "Hanabi {count}" }
         p { "Your name is {name}" }
-        p { "Your score is {game.read().unwrap().score()}" }
+        p { "Your score is {score}" }
+        if let Some(error) = error() {
+            p { class: "error", "{error}" }
+        }
         h2 { "Stacks" }
         ul {
-            for stack in game.read().unwrap().stacks().iter() {
-                li { "{stack:?}" }
+            for stack in stacks.iter() {
+                li { "{stack}" }
             }
         }
         h2 { "Players" }
-        for (i , player) in players.iter().enumerate() {
+        for (i , (player_name, hand)) in view.names.iter().zip(view.hands.iter()).enumerate() {
             div {
-                "Player: {player.name}"
+                "Player: {player_name}"
                 ul {
-                    for card in player.hand.iter() {
-                        if player.name == name {
+                    for (slot, card) in hand.iter().enumerate() {
+                        if i == view.viewer {
                             li {
                                 "???"
                                 button {
@@ -100,13 +104,13 @@ fn Player(name: String) -> Element {
                                         let game = game.clone();
                                         move |_| {
                                             count += 1;
-                                            game.write()
+                                            let result = game.write()
                                                 .unwrap()
                                                 .act(AnnotatedAction {
                                                     player: my_id,
-                                                    action: Action::Play(i),
-                                                })
-                                                .unwrap();
+                                                    action: Action::Play(slot),
+                                                });
+                                            error.set(result.err().map(|err| err.to_string()));
                                         }
                                     },
                                     "Play"
@@ -116,28 +120,28 @@ fn Player(name: String) -> Element {
                                         let game = game.clone();
                                         move |_| {
                                             count += 1;
-                                            game.write()
+                                            let result = game.write()
                                                 .unwrap()
                                                 .act(AnnotatedAction {
                                                     player: my_id,
-                                                    action: Action::Discard(i),
-                                                })
-                                                .unwrap();
+                                                    action: Action::Discard(slot),
+                                                });
+                                            error.set(result.err().map(|err| err.to_string()));
                                         }
                                     },
                                     "Discard"
                                 }
                             }
-                        } else {
+                        } else if let Some(card) = card.card {
                             li {
-                                "{card.card().to_string()}"
+                                "{card.to_string()}"
                                 button {
                                     onclick: {
                                         let game = game.clone();
                                         let suit = card.suit();
                                         move |_| {
                                             count += 1;
-                                            game.write()
+                                            let result = game.write()
                                                 .unwrap()
                                                 .act(AnnotatedAction {
                                                     player: my_id,
@@ -145,8 +149,8 @@ fn Player(name: String) -> Element {
                                                         clue: Clue::Suit(suit),
                                                         target: i,
                                                     },
-                                                })
-                                                .unwrap();
+                                                });
+                                            error.set(result.err().map(|err| err.to_string()));
                                         }
                                     },
                                     "Clue Suit"
@@ -157,7 +161,7 @@ fn Player(name: String) -> Element {
                                         let rank = card.rank();
                                         move |_| {
                                             count += 1;
-                                            game.write()
+                                            let result = game.write()
                                                 .unwrap()
                                                 .act(AnnotatedAction {
                                                     player: my_id,
@@ -165,8 +169,8 @@ fn Player(name: String) -> Element {
                                                         clue: Clue::Rank(rank),
                                                         target: i,
                                                     },
-                                                })
-                                                .unwrap();
+                                                });
+                                            error.set(result.err().map(|err| err.to_string()));
                                         }
                                     },
                                     "Clue Rank"
@@ -180,7 +184,7 @@ fn Player(name: String) -> Element {
 
         h2 { "Discards" }
         ul {
-            for card in game.read().unwrap().discard_pile().iter() {
+            for card in view.discard_pile.iter() {
                 li { "{card.to_string()}" }
             }
         }
@@ -193,7 +197,7 @@ fn Player(name: String) -> Element {
             "Refresh"
         }
         ul {
-            for action in game.read().unwrap().history().iter() {
+            for action in view.history.iter() {
                 li { "{action:?}" }
             }
         }
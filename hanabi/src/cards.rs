@@ -2,9 +2,10 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 use enumflags2::{BitFlag, BitFlags, bitflags};
 use crate::Clue;
+use crate::variants::{ClueFeedback, Variant};
 
 #[bitflags(default = Red | Yellow | Green | Blue | Purple)]
-#[repr(u16)]
+#[repr(u32)]
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Suit {
     Red,
@@ -15,6 +16,35 @@ pub enum Suit {
     Rainbow,
     Black,
     Pink,
+    Teal,
+    Brown,
+    #[serde(rename = "Light Pink")]
+    LightPink,
+    #[serde(rename = "Cocoa Rainbow")]
+    CocoaRainbow,
+    White,
+    Omni,
+    Null,
+    #[serde(rename = "Muddy Rainbow")]
+    MuddyRainbow,
+    #[serde(rename = "Purple Reversed")]
+    PurpleReversed,
+    #[serde(rename = "Teal Reversed")]
+    TealReversed,
+    // The dual-color suits, each touched by two colors.
+    #[serde(rename = "Orange D")]
+    OrangeD,
+    #[serde(rename = "Purple D")]
+    PurpleD,
+    #[serde(rename = "Green D")]
+    GreenD,
+    // The ambiguous suits, which share a color with another suit.
+    Tomato,
+    Mahogany,
+    Sky,
+    Navy,
+    Lime,
+    Forest,
 }
 
 impl TryFrom<&str> for Suit {
@@ -30,6 +60,25 @@ impl TryFrom<&str> for Suit {
             "m" => Ok(Suit::Rainbow),
             "k" => Ok(Suit::Black),
             "i" => Ok(Suit::Pink),
+            "t" => Ok(Suit::Teal),
+            "n" => Ok(Suit::Brown),
+            "l" => Ok(Suit::LightPink),
+            "c" => Ok(Suit::CocoaRainbow),
+            "w" => Ok(Suit::White),
+            "o" => Ok(Suit::Omni),
+            "u" => Ok(Suit::Null),
+            "d" => Ok(Suit::MuddyRainbow),
+            "v" => Ok(Suit::PurpleReversed),
+            "e" => Ok(Suit::TealReversed),
+            "od" => Ok(Suit::OrangeD),
+            "pd" => Ok(Suit::PurpleD),
+            "gd" => Ok(Suit::GreenD),
+            "to" => Ok(Suit::Tomato),
+            "ma" => Ok(Suit::Mahogany),
+            "sk" => Ok(Suit::Sky),
+            "na" => Ok(Suit::Navy),
+            "li" => Ok(Suit::Lime),
+            "fo" => Ok(Suit::Forest),
             _ => Err(()),
         }
     }
@@ -46,6 +95,25 @@ impl From<Suit> for String {
             Suit::Rainbow => "m".to_string(),
             Suit::Black => "k".to_string(),
             Suit::Pink => "i".to_string(),
+            Suit::Teal => "t".to_string(),
+            Suit::Brown => "n".to_string(),
+            Suit::LightPink => "l".to_string(),
+            Suit::CocoaRainbow => "c".to_string(),
+            Suit::White => "w".to_string(),
+            Suit::Omni => "o".to_string(),
+            Suit::Null => "u".to_string(),
+            Suit::MuddyRainbow => "d".to_string(),
+            Suit::PurpleReversed => "v".to_string(),
+            Suit::TealReversed => "e".to_string(),
+            Suit::OrangeD => "od".to_string(),
+            Suit::PurpleD => "pd".to_string(),
+            Suit::GreenD => "gd".to_string(),
+            Suit::Tomato => "to".to_string(),
+            Suit::Mahogany => "ma".to_string(),
+            Suit::Sky => "sk".to_string(),
+            Suit::Navy => "na".to_string(),
+            Suit::Lime => "li".to_string(),
+            Suit::Forest => "fo".to_string(),
         }
     }
 }
@@ -65,6 +133,8 @@ pub enum Rank {
     Three,
     Four,
     Five,
+    /// The START card of "Up or Down", which can begin a stack going either way.
+    Start,
 }
 
 impl TryFrom<&str> for Rank {
@@ -77,6 +147,23 @@ impl TryFrom<&str> for Rank {
             "3" => Ok(Rank::Three),
             "4" => Ok(Rank::Four),
             "5" => Ok(Rank::Five),
+            "s" | "S" => Ok(Rank::Start),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<usize> for Rank {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, ()> {
+        match value {
+            1 => Ok(Rank::One),
+            2 => Ok(Rank::Two),
+            3 => Ok(Rank::Three),
+            4 => Ok(Rank::Four),
+            5 => Ok(Rank::Five),
+            7 => Ok(Rank::Start),
             _ => Err(()),
         }
     }
@@ -90,13 +177,82 @@ impl From<Rank> for usize {
             Rank::Three => 3,
             Rank::Four => 4,
             Rank::Five => 5,
+            // "Hanab Live" numbers START cards as 7.
+            Rank::Start => 7,
         }
     }
 }
 
 impl Display for Rank {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        usize::fmt(&(*self).into(), f)
+        match self {
+            Rank::Start => write!(f, "S"),
+            _ => usize::fmt(&(*self).into(), f),
+        }
+    }
+}
+
+/// The value of a rank clue in "Odds and Evens", which touches every card of that parity.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Parity {
+    Odd,
+    Even,
+}
+
+impl Parity {
+    /// The ranks a clue of this parity touches. START cards have no parity.
+    pub fn ranks(self) -> BitFlags<Rank> {
+        match self {
+            Parity::Odd => Rank::One | Rank::Three | Rank::Five,
+            Parity::Even => Rank::Two | Rank::Four,
+        }
+    }
+
+    pub fn contains(self, rank: Rank) -> bool {
+        self.ranks().contains(rank)
+    }
+}
+
+impl TryFrom<&str> for Parity {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, ()> {
+        match value {
+            "odd" => Ok(Parity::Odd),
+            "even" => Ok(Parity::Even),
+            _ => Err(()),
+        }
+    }
+}
+
+/// "Hanab Live" numbers the odd clue 1 and the even clue 2.
+impl TryFrom<usize> for Parity {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, ()> {
+        match value {
+            1 => Ok(Parity::Odd),
+            2 => Ok(Parity::Even),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<Parity> for usize {
+    fn from(val: Parity) -> Self {
+        match val {
+            Parity::Odd => 1,
+            Parity::Even => 2,
+        }
+    }
+}
+
+impl Display for Parity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Parity::Odd => write!(f, "odd"),
+            Parity::Even => write!(f, "even"),
+        }
     }
 }
 
@@ -129,16 +285,22 @@ impl Card {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AnnotatedCard {
     pub(crate) card: Card,
+    order: usize,
     clues: Vec<Clue>,
+    touched: bool,
     possible_colors: BitFlags<Suit>,
     possible_values: BitFlags<Rank>,
 }
 
 impl AnnotatedCard {
-    pub fn new(card: Card) -> Self {
+    /// Create a card with no clues on it. `order` is the position the card was dealt in, starting
+    /// from 0 for the top of the deck, and identifies the card for the rest of the game.
+    pub fn new(card: Card, order: usize) -> Self {
         Self {
             card,
+            order,
             clues: Vec::new(),
+            touched: false,
             possible_colors: Suit::all(),
             possible_values: Rank::all(),
         }
@@ -148,6 +310,10 @@ impl AnnotatedCard {
         self.card
     }
 
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
     pub fn suit(&self) -> Suit {
         self.card.suit
     }
@@ -160,39 +326,50 @@ impl AnnotatedCard {
         &self.clues
     }
 
-    fn clue_matches(&self, clue: Clue) -> bool {
-        match clue {
-            Clue::Suit(color) => match color {
-                Suit::Rainbow => true,
-                Suit::Black => false,
-                _ => self.suit().eq(&color)
-            }
-            Clue::Rank(value) => match self.card.suit {
-                Suit::Pink => true,
-                _ => self.rank().eq(&value)
-            }
-        }
+    /// The suits this card could still be, given the clues it has received.
+    pub fn possible_colors(&self) -> BitFlags<Suit> {
+        self.possible_colors
+    }
+
+    /// The ranks this card could still be, given the clues it has received.
+    pub fn possible_values(&self) -> BitFlags<Rank> {
+        self.possible_values
     }
 
-    pub fn add_clue(&mut self, clue: Clue) {
+    /// Whether any clue so far has touched this card.
+    pub fn touched(&self) -> bool {
+        self.touched
+    }
+
+    /// Limit what this card could be to the suits and ranks in its variant.
+    pub(crate) fn restrict(&mut self, suits: BitFlags<Suit>, ranks: BitFlags<Rank>) {
+        self.possible_colors &= suits;
+        self.possible_values &= ranks;
+    }
+
+    /// Record a clue given to this card's hand, whether or not it touched this card.
+    pub fn add_clue(&mut self, clue: Clue, variant: &dyn Variant) {
         self.clues.push(clue);
-        self.update_from_clue(clue);
-    }
-
-    fn update_from_clue(&mut self, clue: Clue) {
-        match (clue, self.clue_matches(clue)) {
-            (Clue::Suit(color), true) => {
-                self.possible_colors &= BitFlags::from(color) | BitFlags::from(Suit::Rainbow);
-            },
-            (Clue::Suit(color), false) => {
-                self.possible_colors &= !(BitFlags::from(color) | BitFlags::from(Suit::Rainbow));
-            },
-            (Clue::Rank(value), true) => {
-                self.possible_values = BitFlags::from(value);
-            },
-            (Clue::Rank(value), false) => {
-                self.possible_values.remove(value);
-            },
+        self.update_from_clue(clue, variant);
+    }
+
+    /// Narrow down what this card could be. A suit stays possible if some possible rank of it
+    /// would have reacted to the clue the way this card did, and likewise for ranks.
+    fn update_from_clue(&mut self, clue: Clue, variant: &dyn Variant) {
+        let touched = variant.clue_touches(self.card, clue);
+        self.touched |= touched;
+        // Without knowing what the clue was, there is nothing to narrow down.
+        if variant.clue_feedback() != ClueFeedback::Full {
+            return;
         }
+
+        let (colors, values) = (self.possible_colors, self.possible_values);
+        let consistent = |suit, rank| variant.clue_touches(Card::new(suit, rank), clue) == touched;
+        self.possible_colors = colors.iter()
+            .filter(|&suit| values.iter().any(|rank| consistent(suit, rank)))
+            .collect();
+        self.possible_values = values.iter()
+            .filter(|&rank| colors.iter().any(|suit| consistent(suit, rank)))
+            .collect();
     }
 }
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use crate::cards::Card;
+use crate::stats::Efficiency;
+use crate::Clue;
+use crate::variants::ClueFeedback;
+
+/// Why a game ended, named after the matching "Hanab Live" end conditions.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum EndCondition {
+    /// The final round was played out.
+    Normal,
+    /// The team ran out of bombs.
+    Strikeout,
+    /// An All or Nothing game ended short of the maximum score.
+    AllOrNothingFail,
+}
+
+/// Something that happened as the result of an action, mirroring the game actions that the
+/// "Hanab Live" server sends to its clients. [crate::HanabiGame::act] returns these in the order
+/// they happened.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum GameEvent {
+    /// A clue was given. `list` holds the orders of the cards it touched.
+    Clue {
+        giver: usize,
+        target: usize,
+        clue: Clue,
+        list: Vec<usize>,
+        turn: usize,
+    },
+    /// A card was played successfully.
+    Play {
+        player: usize,
+        order: usize,
+        card: Card,
+    },
+    /// A card was played face down in a variant that hides plays. This is what the players see
+    /// of a [GameEvent::Play] or a misplay until the game ends.
+    HiddenPlay {
+        player: usize,
+        order: usize,
+    },
+    /// A card was discarded. `failed` is set if it was a misplay.
+    Discard {
+        player: usize,
+        order: usize,
+        card: Card,
+        failed: bool,
+    },
+    /// A card was drawn from the deck.
+    Draw {
+        player: usize,
+        order: usize,
+        card: Card,
+    },
+    /// A card was drawn from the deck, as the player who drew it sees it.
+    HiddenDraw {
+        player: usize,
+        order: usize,
+    },
+    /// A misplay cost the team a bomb. `num` counts strikes so far, starting from 1.
+    Strike {
+        num: usize,
+        turn: usize,
+        order: usize,
+    },
+    /// The clue count, score and maximum reachable score after the action, along with the pace
+    /// and clue efficiency described in [crate::stats].
+    Status {
+        clues: usize,
+        score: usize,
+        max_score: usize,
+        pace: isize,
+        efficiency: Efficiency,
+        required_efficiency: Efficiency,
+    },
+    /// The next turn begins. `num` is the number of turns taken so far.
+    Turn {
+        num: usize,
+        current_player: usize,
+    },
+    GameOver {
+        end_condition: EndCondition,
+    },
+}
+
+impl GameEvent {
+    /// Get what every player learns from this event, or `None` if it is kept from them. Draws show
+    /// up as [GameEvent::HiddenDraw], since the drawer doesn't see their card; use
+    /// [crate::HanabiGame::events_for] to get what one seat sees. Clue values are hidden as
+    /// `feedback` requires. If `hidden_plays` is set, plays and misplays show up as
+    /// [GameEvent::HiddenPlay], while strikes and the score are left out.
+    pub fn public(&self, hidden_plays: bool, feedback: ClueFeedback) -> Option<GameEvent> {
+        match *self {
+            GameEvent::Clue { giver, target, clue, ref list, turn } => {
+                return Some(GameEvent::Clue { giver, target, clue: clue.public(feedback), list: list.clone(), turn });
+            }
+            GameEvent::Draw { player, order, .. } => return Some(GameEvent::HiddenDraw { player, order }),
+            _ => {}
+        }
+        if !hidden_plays {
+            return Some(self.clone());
+        }
+        match *self {
+            GameEvent::Play { player, order, .. } | GameEvent::Discard { player, order, failed: true, .. } => {
+                Some(GameEvent::HiddenPlay { player, order })
+            }
+            GameEvent::Strike { .. } | GameEvent::Status { .. } => None,
+            _ => Some(self.clone()),
+        }
+    }
+}
@@ -1,17 +1,25 @@
-use std::collections::HashMap;
-use rand::thread_rng;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock};
+use rand::{thread_rng, Rng, SeedableRng};
 use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use cards::{AnnotatedCard, Card};
-use crate::cards::{Rank, Suit};
-use crate::variants::Variant;
+use enumflags2::BitFlags;
+use crate::cards::{Parity, Rank, Suit};
+use crate::events::{EndCondition, GameEvent};
+use crate::variants::{ClueFeedback, StackOrder, Variant, DESCENDING};
 
 #[cfg(test)]
 mod tests;
 pub mod constants;
 pub mod cards;
+pub mod events;
+pub mod registry;
+pub mod stats;
 pub mod variants;
+pub mod view;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
@@ -28,10 +36,45 @@ impl Player {
     }
 }
 
+/// A clue given to another player. Which clues can be given, and which cards they touch, is up to
+/// the [Variant]; see [Variant::clue_values].
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq)]
 pub enum Clue {
     Suit(Suit),
     Rank(Rank),
+    /// An "odd" or "even" rank clue, given instead of rank clues in "Odds and Evens".
+    Parity(Parity),
+    /// What the players see of a clue whose value is kept from them, as in "Cow & Pig" (which
+    /// still tells color clues from rank clues) and "Duck" (which tells nothing). It can't be
+    /// given.
+    Hidden(Option<ClueKind>),
+}
+
+/// Whether a clue is about color or rank.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq)]
+pub enum ClueKind {
+    Color,
+    Rank,
+}
+
+impl Clue {
+    /// Get whether this is a color or a rank clue, if that is known.
+    pub fn kind(self) -> Option<ClueKind> {
+        match self {
+            Clue::Suit(_) => Some(ClueKind::Color),
+            Clue::Rank(_) | Clue::Parity(_) => Some(ClueKind::Rank),
+            Clue::Hidden(kind) => kind,
+        }
+    }
+
+    /// Get what the players learn of this clue, given how much the variant tells them.
+    pub fn public(self, feedback: ClueFeedback) -> Clue {
+        match feedback {
+            ClueFeedback::Full => self,
+            ClueFeedback::KindOnly => Clue::Hidden(self.kind()),
+            ClueFeedback::Nothing => Clue::Hidden(None),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq)]
@@ -42,6 +85,17 @@ pub enum Action {
     },
     Play(usize),
     Discard(usize),
+    /// Blind play the last card of the deck. Only allowed with [GameConfig::deck_plays].
+    PlayDeck,
+}
+
+/// An action the current player can take, as listed by [HanabiGame::legal_actions].
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LegalAction {
+    pub action: Action,
+    /// The orders of the cards a clue would touch, as given by [HanabiGame::clue_touches]. Empty
+    /// for plays and discards.
+    pub touches: Vec<usize>,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq)]
@@ -50,10 +104,38 @@ pub struct AnnotatedAction {
     pub action: Action,
 }
 
+/// The rules a game is played with. The optional rules match the "Hanab Live" table options of
+/// the same names.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq)]
 pub struct GameConfig {
     pub max_clues: usize,
     pub max_bombs: usize,
+    /// Clues may be given even if they touch no cards.
+    #[serde(default)]
+    pub empty_clues: bool,
+    /// The last card of the deck may be blind played with [Action::PlayDeck].
+    #[serde(default)]
+    pub deck_plays: bool,
+    /// Every player holds one more card than usual.
+    #[serde(default)]
+    pub one_extra_card: bool,
+    /// Every player holds one less card than usual.
+    #[serde(default)]
+    pub one_less_card: bool,
+    /// The game keeps going after the deck runs out until the maximum score is reached, and
+    /// anything less scores 0. Players can only discard with a full hand.
+    #[serde(default)]
+    pub all_or_nothing: bool,
+}
+
+impl GameConfig {
+    /// The number of cards each player is dealt in a game with `num_players` players.
+    pub fn hand_size(&self, num_players: usize) -> Result<usize, DealError> {
+        match constants::CARDS_PER_HAND.get(num_players) {
+            Some(&cards) if cards > 0 => Ok(cards + usize::from(self.one_extra_card) - usize::from(self.one_less_card)),
+            _ => Err(DealError::UnsupportedPlayerCount(num_players)),
+        }
+    }
 }
 
 impl Default for GameConfig {
@@ -61,6 +143,11 @@ impl Default for GameConfig {
         Self {
             max_clues: 8,
             max_bombs: 3,
+            empty_clues: false,
+            deck_plays: false,
+            one_extra_card: false,
+            one_less_card: false,
+            all_or_nothing: false,
         }
     }
 }
@@ -71,6 +158,16 @@ pub enum GameState {
     Ended,
 }
 
+/// Which way a stack is being built.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum StackDirection {
+    /// Nothing has been played yet, or only a START card in "Up or Down".
+    Undecided,
+    Up,
+    Down,
+    Finished,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HanabiGame {
     deck: Vec<AnnotatedCard>,
@@ -84,6 +181,29 @@ pub struct HanabiGame {
     config: GameConfig,
     state: GameState,
     turns_remaining: Option<usize>,
+    seed: Option<u64>,
+    /// The deck as it was before dealing. Saves from before it was kept can't be replayed, so
+    /// they are rejected rather than loaded without it.
+    initial_deck: Vec<Card>,
+    #[serde(with = "variants::by_name")]
+    variant: Arc<dyn Variant>,
+    /// Actions taken back by [HanabiGame::rewind], with the next one to redo last.
+    undone: Vec<AnnotatedAction>,
+    /// The orders of the cards played face down in variants that hide plays, and whether each one
+    /// scored.
+    hole: Vec<(usize, bool)>,
+    /// Half a clue has been given back, in variants where discards only give back half a clue.
+    half_clue: bool,
+    /// How many copies of each card the variant's deck has. Counted when the game is dealt, or on
+    /// first use after loading, since it follows from the variant.
+    #[serde(skip)]
+    copy_counts: OnceLock<BTreeMap<Card, usize>>,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DealError {
+    #[error("A game needs between 2 and {} players, not {0}.", constants::CARDS_PER_HAND.len() - 1)]
+    UnsupportedPlayerCount(usize),
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -94,30 +214,105 @@ pub enum ActError {
     NotEnoughClues,
     #[error("You can't clue yourself.")]
     CantClueYourself,
+    #[error("That player is not in the game.")]
+    NoSuchPlayer,
+    #[error("That clue can't be given in this variant.")]
+    IllegalClue,
     #[error("You can't clue if it doesn't match any cards.")]
     ClueDoesNotMatch,
     #[error("You can't act if the game is over.")]
     GameOver,
+    #[error("That card is not in your hand.")]
+    CardNotInHand,
+    #[error("You can't discard while the team has the maximum number of clues.")]
+    TooManyClues,
+    #[error("You can only discard with a full hand in All or Nothing.")]
+    HandNotFull,
+    #[error("You can only play from the deck when deck plays are on and one card is left.")]
+    CantPlayDeck,
 }
 
 impl HanabiGame {
-    /// Create a new game of Hanabi with the given players.
-    pub fn new(mut players: Vec<Player>, variant: impl Variant) -> Self {
+    /// Create a new game of Hanabi with the given players and rules, shuffled with a random seed.
+    /// Fails if the rules have no hand size for that many players.
+    pub fn new(players: Vec<Player>, variant: impl Variant + 'static, config: GameConfig) -> Result<Self, DealError> {
+        Self::with_seed(players, variant, config, thread_rng().gen())
+    }
+
+    /// Create a new game of Hanabi whose deck is shuffled deterministically from `seed`.
+    /// The seed is kept in the game, so the same players and variant can be re-dealt identically.
+    pub fn with_seed(
+        players: Vec<Player>,
+        variant: impl Variant + 'static,
+        config: GameConfig,
+        seed: u64,
+    ) -> Result<Self, DealError> {
+        let mut game = Self::with_rng(players, variant, config, &mut ChaCha8Rng::seed_from_u64(seed))?;
+        game.seed = Some(seed);
+        Ok(game)
+    }
+
+    /// Create a new game of Hanabi whose deck is shuffled by the given random number generator.
+    pub fn with_rng(
+        players: Vec<Player>,
+        variant: impl Variant + 'static,
+        config: GameConfig,
+        rng: &mut impl Rng,
+    ) -> Result<Self, DealError> {
         let mut deck = variant.starting_deck();
-        deck.shuffle(&mut thread_rng());
+        deck.shuffle(rng);
+        Self::with_deck(players, variant, config, deck)
+    }
 
-        let num_players = players.len();
+    /// Create a new game of Hanabi with an explicit deck, listed from the top (the first card
+    /// dealt) to the bottom. Cards are dealt to the first player until their hand is full, then
+    /// to the second player, and so forth.
+    pub fn with_deck(
+        players: Vec<Player>,
+        variant: impl Variant + 'static,
+        config: GameConfig,
+        deck: Vec<Card>,
+    ) -> Result<Self, DealError> {
+        Self::deal(players, deck, config, Arc::new(variant))
+    }
+
+    /// Internal function to deal `deck` to the players and set up the rest of the table.
+    fn deal(
+        mut players: Vec<Player>,
+        deck: Vec<Card>,
+        config: GameConfig,
+        variant: Arc<dyn Variant>,
+    ) -> Result<Self, DealError> {
+        // The deck is drawn from the back, so store it bottom to top.
+        let initial_deck = deck.clone();
+        let suits = variant.suits().iter().copied().collect();
+        let copy_counts = count_copies(variant.as_ref());
+        let ranks = copy_counts.keys().map(Card::rank).collect();
+        let mut deck: Vec<AnnotatedCard> = deck.into_iter()
+            .enumerate()
+            .map(|(order, card)| {
+                let mut card = AnnotatedCard::new(card, order);
+                card.restrict(suits, ranks);
+                card
+            })
+            .rev()
+            .collect();
+
+        let hand_size = config.hand_size(players.len())?;
         for player in players.iter_mut() {
-            while player.hand.len() < constants::CARDS_PER_HAND[num_players] {
-                if let Some(card) = deck.pop() {
-                    player.hand.push(card);
+            while player.hand.len() < hand_size {
+                match deck.pop() {
+                    Some(card) => player.hand.push(card),
+                    None => break,
                 }
             }
         }
 
-        let config = GameConfig::default();
+        // If the deal used up the deck, the final round starts straight away.
+        let turns_remaining = (deck.is_empty() && !config.all_or_nothing)
+            .then(|| players.len().saturating_sub(1));
 
-        Self {
+        Ok(Self {
             deck,
             players,
             discard_pile: Vec::new(),
@@ -128,12 +323,19 @@ impl HanabiGame {
             stacks: HashMap::new(),
             config,
             state: GameState::InProgress,
-            turns_remaining: None,
-        }
+            turns_remaining,
+            seed: None,
+            initial_deck,
+            variant,
+            undone: Vec::new(),
+            hole: Vec::new(),
+            half_clue: false,
+            copy_counts: OnceLock::from(copy_counts),
+        })
     }
 
-    /// Take a turn in the game, depending on the player's chosen action.
-    pub fn act(&mut self, action: AnnotatedAction) -> Result<(), ActError> {
+    /// Check whether `action` could be taken right now, without taking it.
+    pub fn check_action(&self, action: AnnotatedAction) -> Result<(), ActError> {
         if self.state == GameState::Ended {
             return Err(ActError::GameOver);
         }
@@ -147,19 +349,103 @@ impl HanabiGame {
                 if self.clues == 0 {
                     return Err(ActError::NotEnoughClues);
                 }
+                if target >= self.players.len() {
+                    return Err(ActError::NoSuchPlayer);
+                }
                 if target == action.player {
                     return Err(ActError::CantClueYourself);
                 }
-                self.give_clue(clue, target);
+                if !self.variant.clue_values().contains(&clue) {
+                    return Err(ActError::IllegalClue);
+                }
+                let empty_allowed = self.config.empty_clues || self.variant.clue_touches_nothing(clue);
+                if !empty_allowed && self.clue_touches(clue, target).is_empty() {
+                    return Err(ActError::ClueDoesNotMatch);
+                }
             },
             Action::Play(card) => {
-                self.play_card(card);
+                if card >= self.players[action.player].hand.len() {
+                    return Err(ActError::CardNotInHand);
+                }
             },
             Action::Discard(card) => {
-                self.discard_card(card);
+                if card >= self.players[action.player].hand.len() {
+                    return Err(ActError::CardNotInHand);
+                }
+                if self.clues >= self.config.max_clues {
+                    return Err(ActError::TooManyClues);
+                }
+                // The player count was checked when the game was dealt.
+                let hand_size = self.config.hand_size(self.players.len()).unwrap_or_default();
+                if self.config.all_or_nothing && self.players[action.player].hand.len() < hand_size {
+                    return Err(ActError::HandNotFull);
+                }
+            },
+            Action::PlayDeck => {
+                if !self.config.deck_plays || self.deck.len() != 1 {
+                    return Err(ActError::CantPlayDeck);
+                }
             },
         }
 
+        Ok(())
+    }
+
+    /// List every action the current player could legally take: each play, each discard, and each
+    /// clue that can be given, once per target and clue value, with the cards it would touch. The
+    /// list is empty once the game is over.
+    pub fn legal_actions(&self) -> Vec<LegalAction> {
+        let player = self.current_player;
+        let hand_size = self.players[player].hand.len();
+        let clue_values = self.variant.clue_values();
+        let clues = (0..self.players.len()).flat_map(|target| {
+            clue_values.iter().map(move |&clue| Action::Clue { clue, target })
+        });
+
+        (0..hand_size).map(Action::Play)
+            .chain((0..hand_size).map(Action::Discard))
+            .chain([Action::PlayDeck])
+            .chain(clues)
+            .filter(|&action| self.check_action(AnnotatedAction { player, action }).is_ok())
+            .map(|action| {
+                let touches = match action {
+                    Action::Clue { clue, target } => self.clue_touches(clue, target),
+                    _ => Vec::new(),
+                };
+                LegalAction { action, touches }
+            })
+            .collect()
+    }
+
+    /// Get the orders of the cards in `target`'s hand that `clue` would touch.
+    pub fn clue_touches(&self, clue: Clue, target: usize) -> Vec<usize> {
+        self.players[target].hand.iter()
+            .filter(|annotated_card| self.variant.clue_touches(annotated_card.card, clue))
+            .map(AnnotatedCard::order)
+            .collect()
+    }
+
+    /// Take a turn in the game, depending on the player's chosen action. Returns the events the
+    /// action caused, in the order they happened.
+    pub fn act(&mut self, action: AnnotatedAction) -> Result<Vec<GameEvent>, ActError> {
+        self.check_action(action)?;
+
+        // Taking an action other than the next undone one starts a new line of play.
+        if self.undone.last() == Some(&action) {
+            self.undone.pop();
+        } else {
+            self.undone.clear();
+        }
+
+        let mut events = Vec::new();
+
+        match action.action {
+            Action::Clue { clue, target } => self.give_clue(clue, target, &mut events),
+            Action::Play(card) => self.play_card(card, &mut events),
+            Action::Discard(card) => self.discard_card(card, &mut events),
+            Action::PlayDeck => self.play_deck(&mut events),
+        }
+
         self.game_actions.push(action);
         self.increment_player();
 
@@ -170,65 +456,157 @@ impl HanabiGame {
                 self.turns_remaining = Some(turns_remaining - 1);
             }
         }
+        // Ending early would reveal that every hidden play scored.
+        if self.stacks_score() == self.max_score() && !self.variant.hidden_plays() {
+            self.state = GameState::Ended;
+        }
+        // All or Nothing has no final round, so it ends once nobody can do anything.
+        if self.config.all_or_nothing && self.state == GameState::InProgress && self.legal_actions().is_empty() {
+            self.state = GameState::Ended;
+        }
 
-        Ok(())
+        events.push(GameEvent::Status {
+            clues: self.clues,
+            score: self.score(),
+            max_score: self.max_score(),
+            pace: self.pace(),
+            efficiency: self.efficiency(),
+            required_efficiency: self.required_efficiency(),
+        });
+        events.push(GameEvent::Turn {
+            num: self.game_actions.len(),
+            current_player: self.current_player,
+        });
+        if self.state == GameState::Ended {
+            let end_condition = if self.bombs_left == 0 {
+                EndCondition::Strikeout
+            } else if self.config.all_or_nothing && self.stacks_score() < self.perfect_score() {
+                EndCondition::AllOrNothingFail
+            } else {
+                EndCondition::Normal
+            };
+            events.push(GameEvent::GameOver { end_condition });
+        }
+
+        Ok(events)
     }
 
     /// Internal function to play a card from the current player's hand.
     /// Should only be called from the `act` function.
-    fn play_card(&mut self, card: usize) {
-        let player = &mut self.players[self.current_player];
-        let annotated_card = player.hand.remove(card);
-        let card = annotated_card.card;
-        let stack = self.stacks.entry(card.suit()).or_default();
+    fn play_card(&mut self, card: usize, events: &mut Vec<GameEvent>) {
+        let annotated_card = self.players[self.current_player].hand.remove(card);
+        self.play(annotated_card, events);
+        self.draw_card(self.current_player, events)
+    }
+
+    /// Internal function to blind play the last card of the deck.
+    /// Should only be called from the [HanabiGame::act] function.
+    fn play_deck(&mut self, events: &mut Vec<GameEvent>) {
+        let annotated_card = self.deck.pop().unwrap();
+        events.push(GameEvent::Draw {
+            player: self.current_player,
+            order: annotated_card.order(),
+            card: annotated_card.card,
+        });
+        self.play(annotated_card, events);
+        self.start_final_round();
+    }
 
-        let top = stack.last().map(|card| card.rank() as usize).unwrap_or(0);
-        if top + 1 == card.rank() as usize {
+    /// Internal function to put a card on its stack, or bomb if it doesn't fit.
+    fn play(&mut self, annotated_card: AnnotatedCard, events: &mut Vec<GameEvent>) {
+        let (card, order) = (annotated_card.card, annotated_card.order());
+        let scored = self.playable_ranks(card.suit()).contains(card.rank());
+        let hidden = self.variant.hidden_plays();
+        if hidden {
+            self.hole.push((order, scored));
+        }
+        if scored {
+            let stack = self.stacks.entry(card.suit()).or_default();
             stack.push(card);
-            if card.rank() == Rank::Five {
-                self.clues += 1;
+            // A clue back would give away that a hidden play scored.
+            if stack.len() == 5 && !hidden {
+                self.regain_clue();
             }
+            events.push(GameEvent::Play { player: self.current_player, order, card });
         } else {
+            events.push(GameEvent::Discard { player: self.current_player, order, card, failed: true });
             self.bomb();
+            events.push(GameEvent::Strike {
+                num: self.config.max_bombs - self.bombs_left,
+                turn: self.game_actions.len(),
+                order,
+            });
             self.discard_pile.push(card);
         }
-        self.draw_card(self.current_player)
     }
 
     /// Internal function to discard a card from the current player's hand.
     /// Should only be called from the [HanabiGame::act] function.
-    fn discard_card(&mut self, card: usize) {
+    fn discard_card(&mut self, card: usize, events: &mut Vec<GameEvent>) {
         let player = &mut self.players[self.current_player];
         let annotated_card = player.hand.remove(card);
-        self.clues += 1;
         self.discard_pile.push(annotated_card.card);
-        self.draw_card(self.current_player)
+        self.regain_clue();
+        events.push(GameEvent::Discard {
+            player: self.current_player,
+            order: annotated_card.order(),
+            card: annotated_card.card,
+            failed: false,
+        });
+        self.draw_card(self.current_player, events)
+    }
+
+    /// Internal function to give back a clue for a discard or a finished stack, up to the
+    /// maximum. In "Clue Starved" it takes two of these to make a whole clue.
+    fn regain_clue(&mut self) {
+        if self.clues >= self.config.max_clues {
+            return;
+        }
+        if self.variant.clue_starved() && !self.half_clue {
+            self.half_clue = true;
+            return;
+        }
+        self.half_clue = false;
+        self.clues += 1;
     }
 
     /// Internal function to draw a new card from the deck.
     /// Should only be called from the [HanabiGame::act] function.
-    fn draw_card(&mut self, player: usize) {
-        if self.deck.is_empty() {
-            if let Some(turns_remaining) = self.turns_remaining {
-                self.turns_remaining = Some(turns_remaining - 1);
-            } else {
-                self.turns_remaining = Some(self.players.len());
-            }
+    fn draw_card(&mut self, player: usize, events: &mut Vec<GameEvent>) {
+        let Some(card) = self.deck.pop() else {
             return;
+        };
+        events.push(GameEvent::Draw { player, order: card.order(), card: card.card });
+        self.players[player].hand.insert(0, card);
+        if self.deck.is_empty() {
+            self.start_final_round();
         }
+    }
 
-        let card = self.deck.pop().unwrap();
-        self.players[player].hand.insert(0, card);
+    /// Internal function to give every player one more turn once the last card is drawn,
+    /// including the player who drew it.
+    fn start_final_round(&mut self) {
+        if !self.config.all_or_nothing {
+            // `act` counts down at the end of the turn the card was drawn in, too.
+            self.turns_remaining = Some(self.players.len());
+        }
     }
 
     /// Internal function to give a clue to another player.
     /// Should only be called from the [HanabiGame::act] function.
-    fn give_clue(&mut self, clue: Clue, target: usize) {
+    fn give_clue(&mut self, clue: Clue, target: usize, events: &mut Vec<GameEvent>) {
         self.clues -= 1;
-        let player = &mut self.players[target];
-        for annotated_card in player.hand.iter_mut() {
-            annotated_card.add_clue(clue);
+        let list = self.clue_touches(clue, target);
+        for annotated_card in self.players[target].hand.iter_mut() {
+            annotated_card.add_clue(clue, self.variant.as_ref());
         }
+        events.push(GameEvent::Clue {
+            giver: self.current_player,
+            target,
+            clue,
+            list,
+            turn: self.game_actions.len(),
+        });
     }
 
     /// Internal function to increment the current player.
@@ -246,10 +624,11 @@ impl HanabiGame {
         self.clues
     }
 
-    /// Reduce the number of bombs remaining.
+    /// Reduce the number of bombs remaining. Bombs from hidden plays are only counted, since ending
+    /// the game would reveal them, and the score is lost once the game is over.
     fn bomb(&mut self) {
-        self.bombs_left -= 1;
-        if self.bombs_left == 0 {
+        self.bombs_left = self.bombs_left.saturating_sub(1);
+        if self.bombs_left == 0 && !self.variant.hidden_plays() {
             self.state = GameState::Ended;
         }
     }
@@ -264,14 +643,156 @@ impl HanabiGame {
         &self.players[player].hand
     }
 
+    /// Find the card with the given deal order in a player's hand, returning the player and the
+    /// card's slot in their hand.
+    pub fn find_card(&self, order: usize) -> Option<(usize, usize)> {
+        self.players.iter().enumerate().find_map(|(player, p)| {
+            p.hand.iter().position(|card| card.order() == order).map(|slot| (player, slot))
+        })
+    }
+
+    /// Get the card with the given deal order, if it is in a player's hand.
+    pub fn card_by_order(&self, order: usize) -> Option<&AnnotatedCard> {
+        self.find_card(order).map(|(player, slot)| &self.players[player].hand[slot])
+    }
+
+    /// Play the card with the given deal order from the player's hand.
+    pub fn play_by_order(&mut self, player: usize, order: usize) -> Result<Vec<GameEvent>, ActError> {
+        let slot = self.slot_by_order(player, order)?;
+        self.act(AnnotatedAction { player, action: Action::Play(slot) })
+    }
+
+    /// Discard the card with the given deal order from the player's hand.
+    pub fn discard_by_order(&mut self, player: usize, order: usize) -> Result<Vec<GameEvent>, ActError> {
+        let slot = self.slot_by_order(player, order)?;
+        self.act(AnnotatedAction { player, action: Action::Discard(slot) })
+    }
+
+    /// Get the slot of the card with the given deal order in the player's hand.
+    fn slot_by_order(&self, player: usize, order: usize) -> Result<usize, ActError> {
+        match self.find_card(order) {
+            Some((holder, slot)) if holder == player => Ok(slot),
+            _ => Err(ActError::CardNotInHand),
+        }
+    }
+
     /// Get the current score.
     pub fn score(&self) -> usize {
         if self.bombs_left == 0 {
             return 0;
         }
+        let score = self.stacks_score();
+        if self.config.all_or_nothing && self.state == GameState::Ended && score < self.perfect_score() {
+            return 0;
+        }
+        score
+    }
+
+    /// Get the number of cards on the stacks.
+    pub(crate) fn stacks_score(&self) -> usize {
         self.stacks.values().map(|stack| stack.len()).sum()
     }
 
+    /// Get the highest score the team can still reach, given the cards that have been lost to
+    /// discards and misplays.
+    pub fn max_score(&self) -> usize {
+        self.variant.suits().iter().map(|&suit| self.suit_max(suit, &self.discard_pile)).sum()
+    }
+
+    /// Get the score of a game where no card is lost.
+    fn perfect_score(&self) -> usize {
+        self.variant.suits().iter().map(|&suit| self.suit_max(suit, &[])).sum()
+    }
+
+    /// Get how high the stack of `suit` can be built, if the `lost` cards are gone for good.
+    pub(crate) fn suit_max(&self, suit: Suit, lost: &[Card]) -> usize {
+        self.stack_orders(suit).iter()
+            .map(|order| self.reachable(suit, order, lost))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get how far a stack of `suit` built in `order` can get, if the `lost` cards are gone.
+    fn reachable(&self, suit: Suit, order: &StackOrder, lost: &[Card]) -> usize {
+        order.iter()
+            .take_while(|ranks| ranks.iter().any(|rank| self.copies_left(Card::new(suit, rank), lost) > 0))
+            .count()
+    }
+
+    /// Get the orders of the variant that the stack of `suit` could still be built in, given the
+    /// cards on it and, if starts must be unique, the ranks the other stacks began with.
+    fn stack_orders(&self, suit: Suit) -> Vec<StackOrder> {
+        let stack = self.stacks.get(&suit).map(Vec::as_slice).unwrap_or_default();
+        let taken: BitFlags<Rank> = match self.variant.unique_starts() {
+            true => self.stacks.iter()
+                .filter(|&(&other, _)| other != suit)
+                .filter_map(|(_, stack)| stack.first())
+                .map(Card::rank)
+                .collect(),
+            false => BitFlags::empty(),
+        };
+        self.variant.stack_orders(suit).into_iter()
+            .filter(|order| stack.iter().zip(order).all(|(card, ranks)| ranks.contains(card.rank())))
+            .filter(|order| !taken.contains(order[0]))
+            .collect()
+    }
+
+    /// Get which way the stack of `suit` is being built.
+    pub fn stack_direction(&self, suit: Suit) -> StackDirection {
+        if self.stacks.get(&suit).is_some_and(|stack| stack.len() == 5) {
+            return StackDirection::Finished;
+        }
+        // Only the descending order goes down, whether or not a START card can begin it.
+        let direction = |order: StackOrder| {
+            let without_start = order.map(|mut ranks| {
+                ranks.remove(Rank::Start);
+                ranks
+            });
+            if without_start == DESCENDING { StackDirection::Down } else { StackDirection::Up }
+        };
+        // The direction is settled once every order the stack could still follow agrees on it.
+        let mut directions = self.stack_orders(suit).into_iter().map(direction);
+        match directions.next() {
+            Some(first) if directions.all(|direction| direction == first) => first,
+            _ => StackDirection::Undecided,
+        }
+    }
+
+    /// Get the ranks that can be played on the stack of `suit` right now.
+    pub fn playable_ranks(&self, suit: Suit) -> BitFlags<Rank> {
+        let played = self.stacks.get(&suit).map(Vec::len).unwrap_or(0);
+        self.stack_orders(suit).iter()
+            .filter_map(|order| order.get(played))
+            .fold(BitFlags::empty(), |ranks, &next| ranks | next)
+    }
+
+    /// Get how many copies of `card` the variant's deck has that are not among the `lost` cards.
+    fn copies_left(&self, card: Card, lost: &[Card]) -> usize {
+        let total = self.copy_counts.get_or_init(|| count_copies(self.variant.as_ref()));
+        total.get(&card).copied().unwrap_or(0).saturating_sub(lost.iter().filter(|&&c| c == card).count())
+    }
+
+    /// Whether `card` is still needed for the maximum score and losing it would lower that score.
+    pub fn is_critical(&self, card: Card) -> bool {
+        let lost = [self.discard_pile.as_slice(), &[card]].concat();
+        !self.is_trash(card) && self.suit_max(card.suit(), &lost) < self.suit_max(card.suit(), &self.discard_pile)
+    }
+
+    /// Whether `card` can no longer score, because its place on the stack has been filled already
+    /// or a card it depends on is gone for good.
+    pub fn is_trash(&self, card: Card) -> bool {
+        let played = self.stacks.get(&card.suit()).map(Vec::len).unwrap_or(0);
+        !self.stack_orders(card.suit()).iter().any(|order| {
+            let reachable = self.reachable(card.suit(), order, &self.discard_pile);
+            (played..reachable).any(|place| order[place].contains(card.rank()))
+        })
+    }
+
+    /// Get the rules this game is played with.
+    pub fn config(&self) -> GameConfig {
+        self.config
+    }
+
     /// Get the current player.
     pub fn current_player(&self) -> usize {
         self.current_player
@@ -296,4 +817,110 @@ impl HanabiGame {
     pub fn history(&self) -> &[AnnotatedAction] {
         &self.game_actions
     }
+
+    /// Get every card in the game in the order it was dealt, from the top of the deck to the bottom.
+    pub fn initial_deck(&self) -> &[Card] {
+        &self.initial_deck
+    }
+
+    /// Get the seed the deck was shuffled with, if it was dealt from a seed.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Get the variant this game is played with.
+    pub fn variant(&self) -> &dyn Variant {
+        self.variant.as_ref()
+    }
+
+    /// Whether the outcomes of plays are being kept from the players, as they are until the end
+    /// of a game in variants that hide plays.
+    pub fn concealed(&self) -> bool {
+        self.variant.hidden_plays() && self.state != GameState::Ended
+    }
+
+    /// Get what every player learns from `events`, as returned by [HanabiGame::act] or
+    /// [HanabiGame::redo]. Hidden plays are revealed once the game is over, but clue values the
+    /// variant keeps from the players never are.
+    pub fn public_events(&self, events: &[GameEvent]) -> Vec<GameEvent> {
+        events.iter().filter_map(|event| event.public(self.concealed(), self.variant.clue_feedback())).collect()
+    }
+
+    /// Get what `viewer` learns from `events`: the [HanabiGame::public_events], along with the
+    /// cards the other players drew.
+    pub fn events_for(&self, viewer: usize, events: &[GameEvent]) -> Vec<GameEvent> {
+        events.iter()
+            .filter_map(|event| match *event {
+                GameEvent::Draw { player, .. } if player != viewer => Some(event.clone()),
+                _ => event.public(self.concealed(), self.variant.clue_feedback()),
+            })
+            .collect()
+    }
+
+    /// Get the actions taken so far as every player saw them, with clue values hidden as the
+    /// variant requires.
+    pub fn public_history(&self) -> Vec<AnnotatedAction> {
+        let feedback = self.variant.clue_feedback();
+        self.game_actions.iter()
+            .map(|&AnnotatedAction { player, action }| {
+                let action = match action {
+                    Action::Clue { clue, target } => Action::Clue { clue: clue.public(feedback), target },
+                    action => action,
+                };
+                AnnotatedAction { player, action }
+            })
+            .collect()
+    }
+
+    /// Get a copy of this game as it was after its first `turn` actions, leaving this game
+    /// untouched. The copy can be played on to explore a hypothetical line from that point.
+    pub fn at_turn(&self, turn: usize) -> Self {
+        let players = self.players.iter().map(|player| Player::new(player.name.clone())).collect();
+        let mut game = Self::deal(players, self.initial_deck.clone(), self.config, self.variant.clone())
+            .expect("the game was dealt to these players before");
+        game.seed = self.seed;
+        for &action in self.game_actions.iter().take(turn) {
+            game.act(action).expect("history was legal when it was played");
+        }
+        game
+    }
+
+    /// Go back to how the game was after its first `turn` actions. The actions taken back can be
+    /// replayed with [HanabiGame::redo] until a different action is taken.
+    pub fn rewind(&mut self, turn: usize) {
+        if turn >= self.game_actions.len() {
+            return;
+        }
+        let mut undone = std::mem::take(&mut self.undone);
+        undone.extend(self.game_actions[turn..].iter().rev());
+        *self = self.at_turn(turn);
+        self.undone = undone;
+    }
+
+    /// Take back the last action. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.game_actions.len() {
+            0 => false,
+            turns => {
+                self.rewind(turns - 1);
+                true
+            }
+        }
+    }
+
+    /// Replay the last action taken back by [HanabiGame::undo] or [HanabiGame::rewind], returning
+    /// its events. Returns `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Vec<GameEvent>> {
+        let action = *self.undone.last()?;
+        Some(self.act(action).expect("undone actions were legal when they were played"))
+    }
+}
+
+/// Count how many copies of each card are in the variant's deck.
+fn count_copies(variant: &dyn Variant) -> BTreeMap<Card, usize> {
+    let mut counts = BTreeMap::new();
+    for card in variant.starting_deck() {
+        *counts.entry(card).or_insert(0) += 1;
+    }
+    counts
 }
@@ -0,0 +1,445 @@
+//! Variant definitions loaded from JSON in the style of the "Hanab Live" `suits.json` and
+//! `variants.json` files, so that variants can be looked up by the names and IDs the website uses.
+//!
+//! The bundled files only list the variants this engine knows how to play, under the website's
+//! exact names. A variant's `id` is the number "Hanab Live" puts in seed names; it is only filled
+//! in once it has been checked against the website, so most bundled variants have none yet.
+
+use std::sync::OnceLock;
+use enumflags2::BitFlags;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::cards::{Card, Parity, Rank, Suit};
+use crate::Clue;
+use crate::variants::{ClueFeedback, StackOrder, Variant, ASCENDING, DESCENDING};
+
+const BUNDLED_SUITS: &str = include_str!("../data/suits.json");
+const BUNDLED_VARIANTS: &str = include_str!("../data/variants.json");
+
+#[derive(Error, Debug)]
+pub enum RegistryError {
+    #[error("invalid variant JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("variant {variant:?} uses suit {suit:?}, which is not defined")]
+    UnknownSuit { variant: String, suit: Suit },
+    #[error("variant {variant:?} uses rank {rank}, which does not exist")]
+    InvalidRank { variant: String, rank: usize },
+}
+
+/// How a suit is clued, as listed in `suits.json`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuitDefinition {
+    pub name: Suit,
+    /// The colors that touch this suit. Defaults to the suit's own color.
+    #[serde(default)]
+    pub clue_colors: Option<Vec<Suit>>,
+    #[serde(default)]
+    pub all_clue_colors: bool,
+    #[serde(default)]
+    pub no_clue_colors: bool,
+    #[serde(default)]
+    pub all_clue_ranks: bool,
+    #[serde(default)]
+    pub no_clue_ranks: bool,
+    /// There is only one copy of each rank in this suit.
+    #[serde(default)]
+    pub one_of_each: bool,
+    /// This suit is built from 5 down to 1.
+    #[serde(default)]
+    pub reversed: bool,
+}
+
+impl SuitDefinition {
+    /// Whether `clue` touches `card`, which must be of this suit.
+    pub fn touched_by(&self, card: Card, clue: Clue) -> bool {
+        match clue {
+            Clue::Suit(_) if self.all_clue_colors => true,
+            Clue::Suit(_) if self.no_clue_colors => false,
+            Clue::Suit(color) => self.clue_colors.as_ref().map_or(self.name == color, |colors| colors.contains(&color)),
+            Clue::Rank(_) | Clue::Parity(_) if self.all_clue_ranks => true,
+            Clue::Rank(_) | Clue::Parity(_) if self.no_clue_ranks => false,
+            Clue::Rank(rank) => card.rank() == rank,
+            Clue::Parity(parity) => parity.contains(card.rank()),
+            Clue::Hidden(_) => false,
+        }
+    }
+
+    /// Get the colors of this suit that can be clued directly. Suits touched by every color or
+    /// by none have no color of their own.
+    fn own_colors(&self) -> Vec<Suit> {
+        if self.all_clue_colors || self.no_clue_colors {
+            return Vec::new();
+        }
+        self.clue_colors.clone().unwrap_or_else(|| vec![self.name])
+    }
+}
+
+/// A rank that is clued differently from the rest of its suit, as in "Pink-Ones" or
+/// "Rainbow-Fives".
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SpecialRank {
+    pub rank: Rank,
+    pub all_clue_colors: bool,
+    pub all_clue_ranks: bool,
+    pub no_clue_colors: bool,
+    pub no_clue_ranks: bool,
+}
+
+impl SpecialRank {
+    /// Whether `clue` touches a card of this rank, or `None` if its suit decides.
+    pub fn touched_by(&self, clue: Clue) -> Option<bool> {
+        match clue {
+            Clue::Suit(_) if self.all_clue_colors => Some(true),
+            Clue::Suit(_) if self.no_clue_colors => Some(false),
+            Clue::Rank(_) | Clue::Parity(_) if self.all_clue_ranks => Some(true),
+            Clue::Rank(_) | Clue::Parity(_) if self.no_clue_ranks => Some(false),
+            _ => None,
+        }
+    }
+}
+
+/// A variant as it is written in `variants.json`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariantJson {
+    #[serde(default)]
+    id: Option<u64>,
+    name: String,
+    suits: Vec<Suit>,
+    #[serde(default)]
+    clue_colors: Option<Vec<Suit>>,
+    #[serde(default)]
+    clue_ranks: Option<Vec<usize>>,
+    #[serde(default)]
+    special_rank: Option<usize>,
+    #[serde(default)]
+    special_rank_all_clue_colors: bool,
+    #[serde(default)]
+    special_rank_all_clue_ranks: bool,
+    #[serde(default)]
+    special_rank_no_clue_colors: bool,
+    #[serde(default)]
+    special_rank_no_clue_ranks: bool,
+    #[serde(default)]
+    up_or_down: bool,
+    #[serde(default)]
+    sudoku: bool,
+    #[serde(default)]
+    throw_it_in_hole: bool,
+    #[serde(default)]
+    color_clues_touch_nothing: bool,
+    #[serde(default)]
+    rank_clues_touch_nothing: bool,
+    #[serde(default)]
+    cow_and_pig: bool,
+    #[serde(default)]
+    duck: bool,
+    #[serde(default)]
+    clue_starved: bool,
+    #[serde(default)]
+    critical_rank: Option<usize>,
+    #[serde(default)]
+    odds_and_evens: bool,
+    #[serde(default)]
+    synesthesia: bool,
+}
+
+/// A variant from the registry, with its suits resolved. It can be passed anywhere a [Variant]
+/// is expected.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VariantDefinition {
+    /// The "Hanab Live" ID, if it is known.
+    pub id: Option<u64>,
+    pub name: String,
+    pub suits: Vec<Suit>,
+    pub clue_colors: Vec<Suit>,
+    pub clue_ranks: BitFlags<Rank>,
+    pub special_rank: Option<SpecialRank>,
+    /// The suits that only have one copy of each rank.
+    pub one_of_each: BitFlags<Suit>,
+    /// How each of the suits is clued, in the same order as `suits`.
+    pub suit_definitions: Vec<SuitDefinition>,
+    /// The suits that are built from 5 down to 1.
+    pub reversed: BitFlags<Suit>,
+    /// Stacks can go up or down, and each suit has a START card.
+    pub up_or_down: bool,
+    /// Stacks can begin at any rank and wrap around from 5 to 1, but no two can begin alike.
+    pub sudoku: bool,
+    /// Played cards and strikes are hidden until the game ends.
+    pub throw_it_in_hole: bool,
+    /// Color clues touch no cards, as in "Color Blind" and "Totally Blind".
+    pub color_clues_touch_nothing: bool,
+    /// Rank clues touch no cards, as in "Number Blind" and "Totally Blind".
+    pub rank_clues_touch_nothing: bool,
+    /// What the receiver of a clue learns about it, as changed by "Cow & Pig" and "Duck".
+    pub clue_feedback: ClueFeedback,
+    /// Discards and finished stacks give back half a clue.
+    pub clue_starved: bool,
+    /// The rank with only one copy in each suit, as in "Critical Fours".
+    pub critical_rank: Option<Rank>,
+    /// Rank clues are "odd" or "even" instead of a single rank.
+    pub odds_and_evens: bool,
+    /// The color each rank clue also touches in "Synesthesia", from 1 up. Empty in other
+    /// variants.
+    pub synesthesia_colors: Vec<Suit>,
+}
+
+impl VariantDefinition {
+    fn resolve(json: VariantJson, suits: &[SuitDefinition]) -> Result<Self, RegistryError> {
+        let rank = |rank: usize| {
+            Rank::try_from(rank).map_err(|_| RegistryError::InvalidRank { variant: json.name.clone(), rank })
+        };
+
+        let mut definitions = Vec::new();
+        for &suit in &json.suits {
+            let definition = suits.iter()
+                .find(|definition| definition.name == suit)
+                .ok_or_else(|| RegistryError::UnknownSuit { variant: json.name.clone(), suit })?;
+            definitions.push(definition.clone());
+        }
+
+        // Like "Hanab Live", default to every color the suits can be clued with, in suit order.
+        let mut clue_colors = json.clue_colors.clone().unwrap_or_else(|| {
+            let mut colors = Vec::new();
+            for color in definitions.iter().flat_map(|definition| definition.own_colors()) {
+                if !colors.contains(&color) {
+                    colors.push(color);
+                }
+            }
+            colors
+        });
+        // In "Synesthesia" the colors are given through rank clues instead.
+        let synesthesia_colors = match json.synesthesia {
+            true => std::mem::take(&mut clue_colors),
+            false => Vec::new(),
+        };
+        let clue_ranks = match &json.clue_ranks {
+            Some(ranks) => ranks.iter().map(|&r| rank(r)).collect::<Result<BitFlags<Rank>, _>>()?,
+            None if json.odds_and_evens => BitFlags::empty(),
+            None => BitFlags::default(),
+        };
+        let critical_rank = json.critical_rank.map(rank).transpose()?;
+        let special_rank = match json.special_rank {
+            Some(r) => Some(SpecialRank {
+                rank: rank(r)?,
+                all_clue_colors: json.special_rank_all_clue_colors,
+                all_clue_ranks: json.special_rank_all_clue_ranks,
+                no_clue_colors: json.special_rank_no_clue_colors,
+                no_clue_ranks: json.special_rank_no_clue_ranks,
+            }),
+            None => None,
+        };
+        let one_of_each = definitions.iter()
+            .filter(|definition| definition.one_of_each)
+            .map(|definition| definition.name)
+            .collect();
+        let reversed = definitions.iter()
+            .filter(|definition| definition.reversed)
+            .map(|definition| definition.name)
+            .collect();
+
+        Ok(Self {
+            id: json.id,
+            name: json.name,
+            suits: json.suits,
+            clue_colors,
+            clue_ranks,
+            special_rank,
+            one_of_each,
+            suit_definitions: definitions,
+            reversed,
+            up_or_down: json.up_or_down,
+            sudoku: json.sudoku,
+            throw_it_in_hole: json.throw_it_in_hole,
+            color_clues_touch_nothing: json.color_clues_touch_nothing,
+            rank_clues_touch_nothing: json.rank_clues_touch_nothing,
+            clue_feedback: match (json.cow_and_pig, json.duck) {
+                (_, true) => ClueFeedback::Nothing,
+                (true, false) => ClueFeedback::KindOnly,
+                (false, false) => ClueFeedback::Full,
+            },
+            clue_starved: json.clue_starved,
+            critical_rank,
+            odds_and_evens: json.odds_and_evens,
+            synesthesia_colors,
+        })
+    }
+}
+
+impl VariantDefinition {
+    /// How many copies of `rank` the deck has in `suit`, following "Hanab Live".
+    fn copies(&self, suit: Suit, rank: Rank) -> usize {
+        if rank == Rank::Start {
+            return self.up_or_down as usize;
+        }
+        if self.one_of_each.contains(suit) || self.critical_rank == Some(rank) {
+            return 1;
+        }
+        if self.sudoku {
+            return 2;
+        }
+        match rank {
+            // In "Up or Down" the START card replaces two of the ones.
+            Rank::One if self.up_or_down || self.reversed.contains(suit) => 1,
+            Rank::One => 3,
+            Rank::Five if self.reversed.contains(suit) => 3,
+            Rank::Five => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl Variant for VariantDefinition {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "A variant from the \"Hanab Live\" variant list."
+    }
+
+    fn suits(&self) -> &[Suit] {
+        &self.suits
+    }
+
+    fn clue_colors(&self) -> &[Suit] {
+        &self.clue_colors
+    }
+
+    fn starting_deck(&self) -> Vec<Card> {
+        let mut deck = Vec::new();
+        for &suit in &self.suits {
+            for rank in [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Start] {
+                let copies = self.copies(suit, rank);
+                deck.extend(std::iter::repeat_n(Card::new(suit, rank), copies));
+            }
+        }
+        deck
+    }
+
+    fn legal_rank_clues(&self) -> BitFlags<Rank> {
+        self.clue_ranks
+    }
+
+    fn clue_values(&self) -> Vec<Clue> {
+        let ranks = match self.odds_and_evens {
+            true => vec![Clue::Parity(Parity::Odd), Clue::Parity(Parity::Even)],
+            false => self.clue_ranks.iter().map(Clue::Rank).collect(),
+        };
+        self.clue_colors.iter().map(|&suit| Clue::Suit(suit)).chain(ranks).collect()
+    }
+
+    fn stack_orders(&self, suit: Suit) -> Vec<StackOrder> {
+        if self.sudoku {
+            return (0..5)
+                .map(|start| {
+                    let mut order = ASCENDING;
+                    order.rotate_left(start);
+                    order
+                })
+                .collect();
+        }
+        let with_start = |mut order: StackOrder| {
+            order[0] |= Rank::Start;
+            order
+        };
+        match (self.up_or_down, self.reversed.contains(suit)) {
+            (true, _) => vec![with_start(ASCENDING), with_start(DESCENDING)],
+            (false, true) => vec![DESCENDING],
+            (false, false) => vec![ASCENDING],
+        }
+    }
+
+    fn unique_starts(&self) -> bool {
+        self.sudoku
+    }
+
+    fn hidden_plays(&self) -> bool {
+        self.throw_it_in_hole
+    }
+
+    fn clue_touches_nothing(&self, clue: Clue) -> bool {
+        match clue {
+            Clue::Suit(_) => self.color_clues_touch_nothing,
+            Clue::Rank(_) | Clue::Parity(_) => self.rank_clues_touch_nothing,
+            Clue::Hidden(_) => false,
+        }
+    }
+
+    fn clue_feedback(&self) -> ClueFeedback {
+        self.clue_feedback
+    }
+
+    fn clue_starved(&self) -> bool {
+        self.clue_starved
+    }
+
+    fn clue_touches(&self, card: Card, clue: Clue) -> bool {
+        if self.clue_touches_nothing(clue) {
+            return false;
+        }
+        let special = self.special_rank
+            .filter(|special| special.rank == card.rank())
+            .and_then(|special| special.touched_by(clue));
+        if let Some(touched) = special {
+            return touched;
+        }
+        let Some(definition) = self.suit_definitions.iter().find(|definition| definition.name == card.suit()) else {
+            return false;
+        };
+        let synesthesia_color = match clue {
+            Clue::Rank(rank) => self.synesthesia_colors.get(usize::from(rank) - 1).copied(),
+            _ => None,
+        };
+        definition.touched_by(card, clue)
+            || synesthesia_color.is_some_and(|color| definition.touched_by(card, Clue::Suit(color)))
+    }
+}
+
+/// A set of variants that can be looked up by name or ID.
+#[derive(Clone, Debug)]
+pub struct Registry {
+    suits: Vec<SuitDefinition>,
+    variants: Vec<VariantDefinition>,
+}
+
+impl Registry {
+    /// Load a registry from the contents of a `suits.json` and a `variants.json` file.
+    pub fn from_json(suits: &str, variants: &str) -> Result<Self, RegistryError> {
+        let suits: Vec<SuitDefinition> = serde_json::from_str(suits)?;
+        let variants = serde_json::from_str::<Vec<VariantJson>>(variants)?
+            .into_iter()
+            .map(|variant| VariantDefinition::resolve(variant, &suits))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { suits, variants })
+    }
+
+    /// Get the registry of variants that ship with this crate.
+    pub fn bundled() -> &'static Self {
+        static BUNDLED: OnceLock<Registry> = OnceLock::new();
+        BUNDLED.get_or_init(|| {
+            Self::from_json(BUNDLED_SUITS, BUNDLED_VARIANTS).expect("the bundled variants are valid")
+        })
+    }
+
+    /// Find a variant by its "Hanab Live" name, such as "Rainbow (6 Suits)".
+    pub fn by_name(&self, name: &str) -> Option<&VariantDefinition> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    /// Find a variant by its "Hanab Live" ID, which is what seed names such as `p3v0s12` use.
+    pub fn by_id(&self, id: u64) -> Option<&VariantDefinition> {
+        self.variants.iter().find(|variant| variant.id == Some(id))
+    }
+
+    /// Get every variant in the registry.
+    pub fn variants(&self) -> &[VariantDefinition] {
+        &self.variants
+    }
+
+    /// Get how a suit is clued, if the registry defines it.
+    pub fn suit(&self, suit: Suit) -> Option<&SuitDefinition> {
+        self.suits.iter().find(|definition| definition.name == suit)
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use crate::{Action, HanabiGame};
+
+/// A number of cards gotten for a number of clues, as in H-Group clue efficiency. It is kept as a
+/// ratio of whole numbers so that events holding it can still be compared exactly.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Efficiency {
+    pub cards: usize,
+    pub clues: usize,
+}
+
+impl Efficiency {
+    /// Get the number of cards per clue, or `None` if no clues are involved.
+    pub fn value(&self) -> Option<f64> {
+        (self.clues > 0).then(|| self.cards as f64 / self.clues as f64)
+    }
+}
+
+impl HanabiGame {
+    /// Get the pace: how many more cards can be discarded before the maximum score is out of
+    /// reach. This is the score plus the cards left in the deck plus the number of players, minus
+    /// the maximum score.
+    pub fn pace(&self) -> isize {
+        (self.stacks_score() + self.deck.len() + self.players.len()) as isize - self.max_score() as isize
+    }
+
+    /// Get the number of cards the team has gotten: every card played, plus every clued card in a
+    /// hand that can still score.
+    pub fn cards_gotten(&self) -> usize {
+        let clued = self.players.iter()
+            .flat_map(|player| player.hand.iter())
+            .filter(|card| card.touched() && !self.is_trash(card.card()))
+            .count();
+        self.stacks_score() + clued
+    }
+
+    /// Get the efficiency so far: cards gotten per clue given.
+    pub fn efficiency(&self) -> Efficiency {
+        let clues = self.game_actions.iter()
+            .filter(|action| matches!(action.action, Action::Clue { .. }))
+            .count();
+        Efficiency { cards: self.cards_gotten(), clues }
+    }
+
+    /// Get the efficiency needed from here on to reach the maximum score: the cards not yet gotten
+    /// per clue the team can still give. Those clues are the ones in hand, one for each discard
+    /// the pace allows, and one for each 5 still to be played, or half as many in "Clue Starved".
+    pub fn required_efficiency(&self) -> Efficiency {
+        let fives = self.variant.suits().iter()
+            .filter(|&&suit| {
+                let played = self.stacks.get(&suit).map(Vec::len).unwrap_or(0);
+                played < 5 && self.suit_max(suit, &self.discard_pile) == 5
+            })
+            .count();
+        Efficiency {
+            cards: self.max_score().saturating_sub(self.cards_gotten()),
+            clues: self.clues + self.clues_to_come(self.pace().max(0) as usize + fives),
+        }
+    }
+
+    /// Get how many whole clues `regained` more discards or finished stacks will give back.
+    fn clues_to_come(&self, regained: usize) -> usize {
+        match self.variant.clue_starved() {
+            true => (regained + usize::from(self.half_clue)) / 2,
+            false => regained,
+        }
+    }
+}
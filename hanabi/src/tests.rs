@@ -1,12 +1,16 @@
+use enumflags2::BitFlags;
 use super::*;
 
-fn two_player_game() -> HanabiGame {
-    let variant = variants::NoVariant;
-    let players = vec![
+fn two_players() -> Vec<Player> {
+    vec![
         Player::new("Alice".to_string()),
         Player::new("Bob".to_string()),
-    ];
-    HanabiGame::new(players, variant)
+    ]
+}
+
+fn two_player_game() -> HanabiGame {
+    let variant = variants::NoVariant;
+    HanabiGame::new(two_players(), variant, GameConfig::default()).unwrap()
 }
 
 fn any_legal_clue(game: &mut HanabiGame) -> AnnotatedAction {
@@ -27,10 +31,1444 @@ fn no_more_clues() {
         let clue = any_legal_clue(&mut game);
         let result = game.act(clue);
         assert_eq!(game.clues(), game.config.max_clues - times_clued - 1);
-        assert_eq!(result, Ok(()));
+        assert_eq!(result.map(|_| ()), Ok(()));
     }
 
     let clue = any_legal_clue(&mut game);
     let result = game.act(clue);
     assert_eq!(result, Err(ActError::NotEnoughClues));
 }
+
+#[test]
+fn not_your_turn() {
+    let mut game = two_player_game();
+    let result = game.act(AnnotatedAction { player: 1, action: Action::Play(0) });
+    assert_eq!(result, Err(ActError::NotYourTurn));
+}
+
+#[test]
+fn card_not_in_hand() {
+    let mut game = two_player_game();
+    for action in [Action::Play(5), Action::Discard(5)] {
+        let result = game.act(AnnotatedAction { player: 0, action });
+        assert_eq!(result, Err(ActError::CardNotInHand));
+    }
+    assert_eq!(game.hand(0).len(), 5);
+}
+
+#[test]
+fn clue_nonexistent_player() {
+    let mut game = two_player_game();
+    let action = Action::Clue { clue: Clue::Rank(Rank::One), target: 2 };
+    let result = game.act(AnnotatedAction { player: 0, action });
+    assert_eq!(result, Err(ActError::NoSuchPlayer));
+}
+
+#[test]
+fn clue_yourself() {
+    let mut game = two_player_game();
+    let action = Action::Clue { clue: Clue::Rank(Rank::One), target: 0 };
+    let result = game.act(AnnotatedAction { player: 0, action });
+    assert_eq!(result, Err(ActError::CantClueYourself));
+}
+
+#[test]
+fn clue_not_in_variant() {
+    let mut game = two_player_game();
+    let action = Action::Clue { clue: Clue::Suit(Suit::Rainbow), target: 1 };
+    let result = game.act(AnnotatedAction { player: 0, action });
+    assert_eq!(result, Err(ActError::IllegalClue));
+}
+
+#[test]
+fn clue_does_not_match() {
+    // Bob holds r3 r3 r4 r4 r5.
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    for clue in [Clue::Suit(Suit::Yellow), Clue::Rank(Rank::One)] {
+        let result = game.act(AnnotatedAction { player: 0, action: Action::Clue { clue, target: 1 } });
+        assert_eq!(result, Err(ActError::ClueDoesNotMatch));
+    }
+    assert_eq!(game.clues(), game.config.max_clues);
+}
+
+#[test]
+fn no_discarding_at_max_clues() {
+    let mut game = two_player_game();
+    let result = game.act(AnnotatedAction { player: 0, action: Action::Discard(0) });
+    assert_eq!(result, Err(ActError::TooManyClues));
+
+    let clue = any_legal_clue(&mut game);
+    game.act(clue).unwrap();
+    assert!(game.act(AnnotatedAction { player: 1, action: Action::Discard(0) }).is_ok());
+    assert_eq!(game.clues(), game.config.max_clues);
+}
+
+#[test]
+fn fives_do_not_exceed_max_clues() {
+    // Alice holds the red 1 to 5 and Bob the yellow 1 to 5, so both can play out their suits.
+    let mut deck = variants::NoVariant.starting_deck();
+    let ones_to_fives = |suit| [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five]
+        .map(|rank| Card::new(suit, rank));
+    let top: Vec<Card> = ones_to_fives(Suit::Red).into_iter().chain(ones_to_fives(Suit::Yellow)).collect();
+    for card in &top {
+        let index = deck.iter().position(|c| c == card).unwrap();
+        deck.remove(index);
+    }
+    deck.splice(0..0, top);
+
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    for slot in 0..5 {
+        for player in 0..2 {
+            assert!(game.act(AnnotatedAction { player, action: Action::Play(slot) }).is_ok());
+        }
+    }
+    assert_eq!(game.score(), 10);
+    assert_eq!(game.clues(), game.config.max_clues);
+}
+
+#[test]
+fn game_over() {
+    // Alice holds p5 p4 p4 p3 p3 and Bob p2 p2 p1 p1 p1, so every first card bombs.
+    let mut deck = variants::NoVariant.starting_deck();
+    deck.reverse();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    for player in [0, 1, 0] {
+        assert!(game.act(AnnotatedAction { player, action: Action::Play(0) }).is_ok());
+    }
+    assert_eq!(game.bombs_left(), 0);
+
+    let result = game.act(AnnotatedAction { player: 1, action: Action::Play(0) });
+    assert_eq!(result, Err(ActError::GameOver));
+}
+
+fn hands(game: &HanabiGame) -> Vec<Vec<Card>> {
+    game.players().iter()
+        .map(|player| player.hand.iter().map(AnnotatedCard::card).collect())
+        .collect()
+}
+
+#[test]
+fn same_seed_same_deal() {
+    let game = HanabiGame::with_seed(two_players(), variants::NoVariant, GameConfig::default(), 42).unwrap();
+    let redealt = HanabiGame::with_seed(two_players(), variants::NoVariant, GameConfig::default(), 42).unwrap();
+    assert_eq!(game.seed(), Some(42));
+    assert_eq!(hands(&game), hands(&redealt));
+    assert_eq!(
+        game.deck.iter().map(AnnotatedCard::card).collect::<Vec<_>>(),
+        redealt.deck.iter().map(AnnotatedCard::card).collect::<Vec<_>>(),
+    );
+}
+
+#[test]
+fn seed_survives_serialization() {
+    let game = HanabiGame::with_seed(two_players(), variants::NoVariant, GameConfig::default(), 7).unwrap();
+    let json = serde_json::to_string(&game).unwrap();
+    let loaded: HanabiGame = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.seed(), Some(7));
+
+    let redealt = HanabiGame::with_seed(two_players(), variants::NoVariant, GameConfig::default(), 7).unwrap();
+    assert_eq!(hands(&loaded), hands(&redealt));
+}
+
+#[test]
+fn saves_without_initial_deck_are_rejected() {
+    let game = HanabiGame::with_seed(two_players(), variants::NoVariant, GameConfig::default(), 7).unwrap();
+    let mut json = serde_json::to_value(&game).unwrap();
+    json.as_object_mut().unwrap().remove("initial_deck");
+    let error = serde_json::from_value::<HanabiGame>(json).unwrap_err();
+    assert!(error.to_string().contains("initial_deck"), "{error}");
+}
+
+#[test]
+fn saves_missing_game_state_are_rejected() {
+    let game = HanabiGame::with_seed(two_players(), variants::NoVariant, GameConfig::default(), 7).unwrap();
+    // An old save without these would be loaded as the wrong variant or with state lost.
+    for field in ["variant", "undone", "hole", "half_clue"] {
+        let mut json = serde_json::to_value(&game).unwrap();
+        json.as_object_mut().unwrap().remove(field);
+        let error = serde_json::from_value::<HanabiGame>(json).unwrap_err();
+        assert!(error.to_string().contains(field), "{error}");
+    }
+}
+
+#[test]
+fn saves_missing_card_state_are_rejected() {
+    let game = two_player_game();
+    // Guessing these would mix up which card is which, or forget that a card was clued.
+    for field in ["order", "touched"] {
+        let mut json = serde_json::to_value(&game).unwrap();
+        json["players"][0]["hand"][0].as_object_mut().unwrap().remove(field);
+        let error = serde_json::from_value::<HanabiGame>(json).unwrap_err();
+        assert!(error.to_string().contains(field), "{error}");
+    }
+}
+
+#[test]
+fn copy_counts_come_from_the_variant() {
+    // Even a short explicit deck is scored against the variant's full deck.
+    let deck = [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::One]
+        .map(|rank| Card::new(Suit::Red, rank));
+    let mut game = HanabiGame::with_deck(two_players(), variants::NoVariant, GameConfig::default(), deck.to_vec()).unwrap();
+    assert_eq!(game.max_score(), 25);
+    assert!(!game.is_critical(Card::new(Suit::Red, Rank::One)));
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert_eq!(game.state, GameState::InProgress);
+    // Misplaying the only red five loses it.
+    game.act(AnnotatedAction { player: 1, action: Action::Play(4) }).unwrap();
+    assert_eq!(game.max_score(), 24);
+
+    // The counts are not saved, so a loaded game counts them again.
+    let loaded: HanabiGame = serde_json::from_str(&serde_json::to_string(&game).unwrap()).unwrap();
+    assert!(loaded.copy_counts.get().is_none());
+    assert_eq!(loaded.max_score(), 24);
+    assert_eq!(loaded.copy_counts.get(), game.copy_counts.get());
+}
+
+#[test]
+fn explicit_deck_is_dealt_in_order() {
+    let deck = variants::NoVariant.starting_deck();
+    let game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck.clone()).unwrap();
+    assert_eq!(game.seed(), None);
+    assert_eq!(hands(&game), vec![deck[0..5].to_vec(), deck[5..10].to_vec()]);
+    assert_eq!(game.deck.last().map(AnnotatedCard::card), Some(deck[10]));
+}
+
+#[test]
+fn stacks_build_past_two() {
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    for (player, action) in [(0, Action::Play(0)), (1, Action::Clue { clue: Clue::Rank(Rank::Two), target: 0 }), (0, Action::Play(3)), (1, Action::Play(1))] {
+        assert!(game.act(AnnotatedAction { player, action }).is_ok());
+    }
+    assert_eq!(game.score(), 3);
+    assert_eq!(game.bombs_left(), game.config.max_bombs);
+}
+
+#[test]
+fn cards_keep_their_deal_order() {
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck.clone()).unwrap();
+    let orders: Vec<usize> = game.hand(1).iter().map(AnnotatedCard::order).collect();
+    assert_eq!(orders, vec![5, 6, 7, 8, 9]);
+    assert_eq!(game.find_card(7), Some((1, 2)));
+    assert_eq!(game.card_by_order(7).map(AnnotatedCard::card), Some(deck[7]));
+
+    assert_eq!(game.discard_by_order(0, 7), Err(ActError::CardNotInHand));
+    assert!(game.play_by_order(0, 3).is_ok());
+    assert_eq!(game.find_card(3), None);
+    assert_eq!(game.find_card(10), Some((0, 0)));
+
+    assert!(game.play_by_order(1, 5).is_ok());
+    assert_eq!(game.discard_pile(), &[deck[3], deck[5]]);
+    assert_eq!(game.find_card(11), Some((1, 0)));
+}
+
+#[test]
+fn view_hides_own_cards() {
+    let mut game = two_player_game();
+    let clue = any_legal_clue(&mut game);
+    game.act(clue).unwrap();
+
+    let view = game.view(1);
+    assert!(view.my_turn());
+    assert!(view.my_hand().iter().all(|card| card.card.is_none()));
+    assert!(view.hands[0].iter().all(|card| card.card.is_some()));
+
+    // Bob still learns what Alice's clue told him about his cards.
+    for (seen, card) in view.my_hand().iter().zip(game.hand(1)) {
+        assert_eq!(seen.clues, card.clues());
+        assert_eq!(seen.possible_colors, card.possible_colors());
+        assert_eq!(seen.possible_values, card.possible_values());
+    }
+    assert_eq!(view.clues, game.clues());
+    assert_eq!(view.history, game.history());
+}
+
+#[test]
+fn act_reports_events() {
+    use events::GameEvent::{Discard, Draw, Play, Status, Strike, Turn};
+    use stats::Efficiency;
+
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck.clone()).unwrap();
+
+    let events = game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert_eq!(events, vec![
+        Play { player: 0, order: 0, card: deck[0] },
+        Draw { player: 0, order: 10, card: deck[10] },
+        Status {
+            clues: 8,
+            score: 1,
+            max_score: 25,
+            pace: 17,
+            efficiency: Efficiency { cards: 1, clues: 0 },
+            required_efficiency: Efficiency { cards: 24, clues: 30 },
+        },
+        Turn { num: 1, current_player: 1 },
+    ]);
+
+    let events = game.act(AnnotatedAction { player: 1, action: Action::Play(0) }).unwrap();
+    assert_eq!(events, vec![
+        Discard { player: 1, order: 5, card: deck[5], failed: true },
+        Strike { num: 1, turn: 1, order: 5 },
+        Draw { player: 1, order: 11, card: deck[11] },
+        Status {
+            clues: 8,
+            score: 1,
+            max_score: 25,
+            pace: 16,
+            efficiency: Efficiency { cards: 1, clues: 0 },
+            required_efficiency: Efficiency { cards: 24, clues: 29 },
+        },
+        Turn { num: 2, current_player: 0 },
+    ]);
+
+    let clue = Action::Clue { clue: Clue::Rank(Rank::Three), target: 1 };
+    let events = game.act(AnnotatedAction { player: 0, action: clue }).unwrap();
+    assert_eq!(events, vec![
+        events::GameEvent::Clue { giver: 0, target: 1, clue: Clue::Rank(Rank::Three), list: vec![6], turn: 2 },
+        Status {
+            clues: 7,
+            score: 1,
+            max_score: 25,
+            pace: 16,
+            efficiency: Efficiency { cards: 2, clues: 1 },
+            required_efficiency: Efficiency { cards: 23, clues: 28 },
+        },
+        Turn { num: 3, current_player: 1 },
+    ]);
+}
+
+/// Get the actions [HanabiGame::legal_actions] lists, without the cards they touch.
+fn legal_actions(game: &HanabiGame) -> Vec<Action> {
+    game.legal_actions().into_iter().map(|legal| legal.action).collect()
+}
+
+#[test]
+fn legal_actions_match_act() {
+    // Alice holds r1 r1 r1 r2 r2 and Bob r3 r3 r4 r4 r5.
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+
+    // With every clue available, Alice can't discard, and Bob can only be clued red, 3, 4 or 5.
+    let actions = game.legal_actions();
+    let clue = |clue| Action::Clue { clue, target: 1 };
+    let legal = |action, touches: &[usize]| LegalAction { action, touches: touches.to_vec() };
+    let mut expected: Vec<LegalAction> = (0..5).map(|slot| legal(Action::Play(slot), &[])).collect();
+    expected.extend([
+        legal(clue(Clue::Suit(Suit::Red)), &[5, 6, 7, 8, 9]),
+        legal(clue(Clue::Rank(Rank::Three)), &[5, 6]),
+        legal(clue(Clue::Rank(Rank::Four)), &[7, 8]),
+        legal(clue(Clue::Rank(Rank::Five)), &[9]),
+    ]);
+    assert_eq!(actions, expected);
+    assert_eq!(game.clue_touches(Clue::Rank(Rank::Four), 1), vec![7, 8]);
+
+    for action in [Action::Discard(0), clue(Clue::Suit(Suit::Blue)), Action::Play(5)] {
+        assert!(game.check_action(AnnotatedAction { player: 0, action }).is_err());
+    }
+    for LegalAction { action, .. } in actions {
+        assert!(game.clone().act(AnnotatedAction { player: 0, action }).is_ok());
+    }
+
+    game.act(AnnotatedAction { player: 0, action: clue(Clue::Rank(Rank::Five)) }).unwrap();
+    assert!(legal_actions(&game).contains(&Action::Discard(4)));
+}
+
+#[test]
+fn rewind_and_redo() {
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    let actions = [
+        AnnotatedAction { player: 0, action: Action::Play(0) },
+        AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Rank(Rank::Two), target: 0 } },
+        AnnotatedAction { player: 0, action: Action::Play(3) },
+    ];
+    let mut snapshots = vec![hands(&game)];
+    for action in actions {
+        game.act(action).unwrap();
+        snapshots.push(hands(&game));
+    }
+
+    game.rewind(1);
+    assert_eq!(game.history(), &actions[..1]);
+    assert_eq!(hands(&game), snapshots[1]);
+    assert_eq!(game.clues(), game.config.max_clues);
+    assert!(game.hand(0).iter().all(|card| card.clues().is_empty()));
+
+    assert!(game.undo());
+    assert!(!game.undo());
+    assert_eq!(hands(&game), snapshots[0]);
+
+    while game.redo().is_some() {}
+    assert_eq!(game.history(), &actions);
+    assert_eq!(hands(&game), snapshots[3]);
+    assert_eq!(game.score(), 2);
+
+    // Taking a different action drops the rest of the undone line.
+    game.rewind(1);
+    game.act(AnnotatedAction { player: 1, action: Action::Play(0) }).unwrap();
+    assert_eq!(game.redo(), None);
+}
+
+#[test]
+fn branching_leaves_the_original_alone() {
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    game.act(AnnotatedAction { player: 1, action: Action::Play(0) }).unwrap();
+
+    let mut hypo = game.at_turn(1);
+    hypo.act(AnnotatedAction { player: 1, action: Action::Play(4) }).unwrap();
+    assert_eq!(hypo.bombs_left(), 2);
+    assert_eq!(hypo.history().len(), 2);
+
+    assert_eq!(game.bombs_left(), 2);
+    assert_eq!(game.history()[1].action, Action::Play(0));
+    assert_eq!(game.discard_pile().len(), 1);
+}
+
+#[test]
+fn hand_size_follows_options() {
+    let config = GameConfig { one_extra_card: true, ..GameConfig::default() };
+    let game = HanabiGame::new(two_players(), variants::NoVariant, config).unwrap();
+    assert!(game.players().iter().all(|player| player.hand.len() == 6));
+    assert_eq!(game.config(), config);
+
+    let five_players = (0..5).map(|i| Player::new(i.to_string())).collect();
+    let config = GameConfig { one_less_card: true, ..GameConfig::default() };
+    let game = HanabiGame::new(five_players, variants::NoVariant, config).unwrap();
+    assert!(game.players().iter().all(|player| player.hand.len() == 3));
+}
+
+#[test]
+fn unsupported_player_counts_are_rejected() {
+    let config = GameConfig { one_less_card: true, ..GameConfig::default() };
+    for count in [0, 1, 9] {
+        let players = (0..count).map(|i| Player::new(i.to_string())).collect();
+        let result = HanabiGame::new(players, variants::NoVariant, config);
+        assert_eq!(result.err(), Some(DealError::UnsupportedPlayerCount(count)));
+    }
+}
+
+#[test]
+fn empty_clues() {
+    // Bob holds r3 r3 r4 r4 r5.
+    let deck = variants::NoVariant.starting_deck();
+    let config = GameConfig { empty_clues: true, ..GameConfig::default() };
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, config, deck).unwrap();
+    let clue = Action::Clue { clue: Clue::Suit(Suit::Yellow), target: 1 };
+    let events = game.act(AnnotatedAction { player: 0, action: clue }).unwrap();
+    assert!(matches!(&events[0], events::GameEvent::Clue { list, .. } if list.is_empty()));
+    assert_eq!(game.clues(), game.config.max_clues - 1);
+}
+
+#[test]
+fn final_round_gives_everyone_one_more_turn() {
+    // Bob draws the last card on the second turn, so the game ends after the fourth.
+    let deck = variants::NoVariant.starting_deck()[..12].to_vec();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    let clue = |target| Action::Clue { clue: Clue::Suit(Suit::Red), target };
+    for (player, action) in [(0, Action::Play(0)), (1, Action::Play(0)), (0, clue(1))] {
+        game.act(AnnotatedAction { player, action }).unwrap();
+        assert_eq!(game.state, GameState::InProgress);
+    }
+    let events = game.act(AnnotatedAction { player: 1, action: clue(0) }).unwrap();
+    assert_eq!(events.last(), Some(&events::GameEvent::GameOver { end_condition: events::EndCondition::Normal }));
+}
+
+#[test]
+fn deck_plays() {
+    // One card is left in the deck after the deal: y1.
+    let deck = variants::NoVariant.starting_deck()[..11].to_vec();
+    let game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck.clone()).unwrap();
+    let play_deck = AnnotatedAction { player: 0, action: Action::PlayDeck };
+    assert_eq!(game.check_action(play_deck), Err(ActError::CantPlayDeck));
+    assert!(!legal_actions(&game).contains(&Action::PlayDeck));
+
+    let config = GameConfig { deck_plays: true, ..GameConfig::default() };
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, config, deck.clone()).unwrap();
+    assert!(legal_actions(&game).contains(&Action::PlayDeck));
+    let events = game.act(play_deck).unwrap();
+    assert_eq!(events[..2], [
+        events::GameEvent::Draw { player: 0, order: 10, card: deck[10] },
+        events::GameEvent::Play { player: 0, order: 10, card: deck[10] },
+    ]);
+    assert_eq!(game.hand(0).len(), 5);
+    assert_eq!(game.score(), 1);
+
+    // Playing from the deck drew its last card, so the final round has begun.
+    let clue = |target| Action::Clue { clue: Clue::Suit(Suit::Red), target };
+    game.act(AnnotatedAction { player: 1, action: clue(0) }).unwrap();
+    game.act(AnnotatedAction { player: 0, action: clue(1) }).unwrap();
+    assert_eq!(game.state, GameState::Ended);
+}
+
+#[test]
+fn all_or_nothing() {
+    // Alice draws the last card on the first turn, but there is no final round.
+    let deck = variants::NoVariant.starting_deck()[..11].to_vec();
+    let config = GameConfig { all_or_nothing: true, ..GameConfig::default() };
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, config, deck).unwrap();
+    let clue = Action::Clue { clue: Clue::Rank(Rank::Four), target: 1 };
+    for (player, action) in [(0, Action::Play(0)), (1, Action::Play(0)), (0, clue)] {
+        game.act(AnnotatedAction { player, action }).unwrap();
+    }
+    assert_eq!(game.state, GameState::InProgress);
+    assert_eq!(game.score(), 1);
+
+    // Bob misplayed without drawing a replacement.
+    let result = game.act(AnnotatedAction { player: 1, action: Action::Discard(0) });
+    assert_eq!(result, Err(ActError::HandNotFull));
+}
+
+/// Two suits with one of each rank, so that a whole deck fits in two hands.
+#[derive(Debug)]
+struct TwoSuits;
+impl Variant for TwoSuits {
+    fn name(&self) -> &str {
+        "Two Suits"
+    }
+
+    fn description(&self) -> &str {
+        "Red and yellow, one of each rank."
+    }
+
+    fn suits(&self) -> &[Suit] {
+        &[Suit::Red, Suit::Yellow]
+    }
+
+    fn clue_colors(&self) -> &[Suit] {
+        self.suits()
+    }
+
+    fn starting_deck(&self) -> Vec<Card> {
+        let ones_to_fives = |suit| [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five]
+            .map(|rank| Card::new(suit, rank));
+        ones_to_fives(Suit::Red).into_iter().chain(ones_to_fives(Suit::Yellow)).collect()
+    }
+
+    fn legal_rank_clues(&self) -> BitFlags<Rank> {
+        BitFlags::default()
+    }
+}
+
+#[test]
+fn game_ends_at_max_score() {
+    // Alice holds the red 1 to 5 and Bob the yellow 1 to 5, which is the whole deck.
+    let deck = TwoSuits.starting_deck();
+    let config = GameConfig { all_or_nothing: true, ..GameConfig::default() };
+    let mut game = HanabiGame::with_deck(two_players(), TwoSuits, config, deck).unwrap();
+    // Nothing is drawn, so the next card is always in the first slot.
+    for _ in 0..5 {
+        for player in 0..2 {
+            game.act(AnnotatedAction { player, action: Action::Play(0) }).unwrap();
+        }
+    }
+    assert_eq!(game.state, GameState::Ended);
+    assert_eq!(game.score(), 10);
+}
+
+#[test]
+fn discards_lower_the_max_score() {
+    // Alice holds r1 r1 r1 r2 r2 and Bob r3 r3 r4 r4 r5.
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    let red = |rank| Card::new(Suit::Red, rank);
+    assert_eq!(game.max_score(), 25);
+    assert!(game.is_critical(red(Rank::Five)));
+    assert!(!game.is_critical(red(Rank::Two)));
+
+    // Alice plays r1, so the other r1s are trash.
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert!(game.is_trash(red(Rank::One)));
+    assert!(!game.is_trash(red(Rank::Two)));
+
+    // Losing one r2 makes the other critical, and losing both caps red at 1.
+    let events = game.act(AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Rank(Rank::Two), target: 0 } }).unwrap();
+    assert!(events.iter().any(|event| matches!(event, events::GameEvent::Status { clues: 7, max_score: 25, .. })));
+    game.act(AnnotatedAction { player: 0, action: Action::Discard(4) }).unwrap();
+    assert!(game.is_critical(red(Rank::Two)));
+    assert_eq!(game.max_score(), 25);
+
+    game.act(AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Rank(Rank::One), target: 0 } }).unwrap();
+    let events = game.act(AnnotatedAction { player: 0, action: Action::Discard(4) }).unwrap();
+    assert!(events.iter().any(|event| matches!(event, events::GameEvent::Status { clues: 8, max_score: 21, .. })));
+    assert_eq!(game.max_score(), 21);
+    assert!(game.is_trash(red(Rank::Five)));
+    assert!(!game.is_critical(red(Rank::Five)));
+}
+
+#[test]
+fn pace_and_efficiency() {
+    // Alice holds r1 r1 r1 r2 r2 and Bob r3 r3 r4 r4 r5.
+    let deck = variants::NoVariant.starting_deck();
+    let mut game = HanabiGame::with_deck(two_players(), &variants::NoVariant, GameConfig::default(), deck).unwrap();
+    assert_eq!(game.pace(), 40 + 2 - 25);
+    assert_eq!(game.efficiency().value(), None);
+
+    // A 2 clue gets both r2s, but only one of them counts once the other is discarded.
+    let clue = Action::Clue { clue: Clue::Rank(Rank::Two), target: 0 };
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    game.act(AnnotatedAction { player: 1, action: clue }).unwrap();
+    assert_eq!(game.efficiency(), stats::Efficiency { cards: 3, clues: 1 });
+    assert_eq!(game.efficiency().value(), Some(3.0));
+
+    game.act(AnnotatedAction { player: 0, action: Action::Discard(4) }).unwrap();
+    assert_eq!(game.efficiency(), stats::Efficiency { cards: 2, clues: 1 });
+    assert_eq!(game.pace(), 1 + 38 + 2 - 25);
+    assert_eq!(game.required_efficiency(), stats::Efficiency { cards: 23, clues: 8 + 16 + 5 });
+    assert_eq!(game.view(1).pace, Some(game.pace()));
+}
+
+#[test]
+fn bundled_variants_resolve() {
+    let registry = registry::Registry::bundled();
+    let no_variant = registry.by_name("No Variant").unwrap();
+    assert_eq!(no_variant.id, Some(0));
+    assert_eq!(registry.by_id(0).map(|variant| variant.name.as_str()), Some("No Variant"));
+    assert_eq!(no_variant.starting_deck(), variants::NoVariant.starting_deck());
+    assert_eq!(no_variant.legal_suit_clues(), variants::NoVariant.legal_suit_clues());
+
+    // Rainbow has no color of its own, so it adds a suit but no clue color.
+    let rainbow = registry.by_name("Rainbow (6 Suits)").unwrap();
+    assert_eq!(rainbow.suits(), variants::Rainbow6Variant.suits());
+    assert_eq!(rainbow.clue_colors(), variants::Rainbow6Variant.clue_colors());
+    assert_eq!(rainbow.starting_deck().len(), 60);
+
+    let six_suits = registry.by_name("6 Suits").unwrap();
+    assert_eq!(six_suits.name, "6 Suits");
+    assert_eq!(six_suits.clue_colors.last(), Some(&Suit::Teal));
+    assert!(registry.by_name("Not A Variant").is_none());
+
+    for variant in registry.variants() {
+        let mut game = HanabiGame::new(two_players(), variant.clone(), GameConfig::default()).unwrap();
+        let action = game.legal_actions()[0].action;
+        assert!(game.act(AnnotatedAction { player: 0, action }).is_ok(), "{}", variant.name);
+    }
+}
+
+#[test]
+fn registry_reads_variant_options() {
+    let suits = r#"[{ "name": "Red" }, { "name": "Blue", "oneOfEach": true }]"#;
+    let variants = r#"[{ "id": 9, "name": "Test", "suits": ["Red", "Blue"], "clueRanks": [1, 5], "specialRank": 1, "specialRankAllClueRanks": true }]"#;
+    let registry = registry::Registry::from_json(suits, variants).unwrap();
+    let variant = registry.by_id(9).unwrap();
+    assert_eq!(variant.name, "Test");
+    assert_eq!(variant.starting_deck().len(), 15);
+    assert_eq!(variant.clue_colors(), &[Suit::Red, Suit::Blue]);
+    assert_eq!(variant.legal_rank_clues(), Rank::One | Rank::Five);
+    assert_eq!(variant.special_rank.map(|special| (special.rank, special.all_clue_ranks)), Some((Rank::One, true)));
+    assert!(registry.suit(Suit::Blue).unwrap().one_of_each);
+
+    let variants = r#"[{ "name": "Test", "suits": ["Red", "Green"] }]"#;
+    let result = registry::Registry::from_json(suits, variants);
+    assert!(matches!(result, Err(registry::RegistryError::UnknownSuit { suit: Suit::Green, .. })));
+}
+
+#[test]
+fn game_keeps_its_variant() {
+    let mut game = HanabiGame::new(two_players(), variants::Rainbow6Variant, GameConfig::default()).unwrap();
+    assert_eq!(game.variant().name(), "Rainbow (6 Suits)");
+    assert_eq!(game.view(0).suits, variants::Rainbow6Variant.suits());
+    assert_eq!(game.max_score(), 30);
+
+    // Rainbow is a suit, but not a color that can be clued.
+    let action = Action::Clue { clue: Clue::Suit(Suit::Rainbow), target: 1 };
+    assert_eq!(game.check_action(AnnotatedAction { player: 0, action }), Err(ActError::IllegalClue));
+    assert!(!legal_actions(&game).contains(&action));
+
+    // Saved games remember their variant by name.
+    let json = serde_json::to_value(&game).unwrap();
+    assert_eq!(json["variant"], "Rainbow (6 Suits)");
+    let mut loaded: HanabiGame = serde_json::from_value(json).unwrap();
+    assert_eq!(loaded.variant().suits(), game.variant().suits());
+    let action = game.legal_actions()[0].action;
+    assert_eq!(loaded.act(AnnotatedAction { player: 0, action }), game.act(AnnotatedAction { player: 0, action }));
+}
+
+#[test]
+fn black_has_one_of_each_rank() {
+    let deck = variants::Black6Variant.starting_deck();
+    assert_eq!(deck.len(), 55);
+    let black: Vec<Rank> = deck.iter().filter(|card| card.suit() == Suit::Black).map(Card::rank).collect();
+    assert_eq!(black, [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five]);
+
+    let registered = registry::Registry::bundled().by_name("Black (6 Suits)").unwrap();
+    assert_eq!(registered.starting_deck(), deck);
+    assert_eq!(registered.clue_colors(), variants::Black6Variant.clue_colors());
+
+    // Every black card is critical from the start, and the max score drops if one is lost.
+    let game = HanabiGame::with_deck(two_players(), variants::Black6Variant, GameConfig::default(), deck).unwrap();
+    assert!(game.is_critical(Card::new(Suit::Black, Rank::One)));
+    assert!(!game.is_critical(Card::new(Suit::Red, Rank::One)));
+    assert_eq!(game.max_score(), 30);
+}
+
+#[test]
+fn black_is_clued_by_black() {
+    let variant = variants::Black6Variant;
+    let touches = |suit, clue| variant.clue_touches(Card::new(suit, Rank::Two), clue);
+    assert!(touches(Suit::Black, Clue::Suit(Suit::Black)));
+    assert!(touches(Suit::Rainbow, Clue::Suit(Suit::Black)));
+    assert!(!touches(Suit::Red, Clue::Suit(Suit::Black)));
+    assert!(!touches(Suit::Black, Clue::Suit(Suit::Red)));
+
+    let mut touched = AnnotatedCard::new(Card::new(Suit::Black, Rank::Two), 0);
+    touched.add_clue(Clue::Suit(Suit::Black), &variant);
+    assert!(touched.possible_colors().contains(Suit::Black));
+    assert!(!touched.possible_colors().contains(Suit::Red));
+
+    let mut missed = AnnotatedCard::new(Card::new(Suit::Red, Rank::Two), 1);
+    missed.add_clue(Clue::Suit(Suit::Black), &variant);
+    assert!(!missed.possible_colors().contains(Suit::Black));
+}
+
+/// Deal `card` on its own in `variant`, so it can only be one of the variant's suits.
+fn clued_card(variant: &str, card: Card, clues: &[Clue]) -> AnnotatedCard {
+    let variant = registry::Registry::bundled().by_name(variant).unwrap();
+    let mut annotated = AnnotatedCard::new(card, 0);
+    let ranks = variant.starting_deck().iter().map(Card::rank).collect();
+    annotated.restrict(suits(variant.suits()), ranks);
+    for &clue in clues {
+        annotated.add_clue(clue, variant);
+    }
+    annotated
+}
+
+fn suits(suits: &[Suit]) -> BitFlags<Suit> {
+    suits.iter().copied().collect()
+}
+
+fn ranks(ranks: &[Rank]) -> BitFlags<Rank> {
+    ranks.iter().copied().collect()
+}
+
+#[test]
+fn pink_is_touched_by_every_rank() {
+    let all = [Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple, Suit::Pink];
+
+    // A three clue on a pink card could mean any rank, but rules out the other suits' non-threes.
+    let card = clued_card("Pink (6 Suits)", Card::new(Suit::Pink, Rank::One), &[Clue::Rank(Rank::Three)]);
+    assert!(card.touched());
+    assert_eq!(card.possible_colors(), suits(&all));
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    // Missing a three means the card is neither pink nor a three.
+    let card = clued_card("Pink (6 Suits)", Card::new(Suit::Red, Rank::One), &[Clue::Rank(Rank::Three)]);
+    assert!(!card.touched());
+    assert_eq!(card.possible_colors(), suits(&all[..5]));
+    assert_eq!(card.possible_values(), ranks(&[Rank::One, Rank::Two, Rank::Four, Rank::Five]));
+
+    // Once the color is known, a rank clue says nothing about a pink card.
+    let card = clued_card(
+        "Pink (6 Suits)",
+        Card::new(Suit::Pink, Rank::Four),
+        &[Clue::Suit(Suit::Pink), Clue::Rank(Rank::Two)],
+    );
+    assert_eq!(card.possible_colors(), suits(&[Suit::Pink]));
+    assert_eq!(card.possible_values(), BitFlags::default());
+}
+
+#[test]
+fn brown_is_touched_by_no_rank() {
+    let card = clued_card("Brown (6 Suits)", Card::new(Suit::Brown, Rank::Five), &[Clue::Rank(Rank::Five)]);
+    assert!(!card.touched());
+    assert_eq!(card.possible_values(), BitFlags::default());
+    assert!(card.possible_colors().contains(Suit::Brown));
+
+    // A rank clue that touches a card rules brown out.
+    let card = clued_card("Brown (6 Suits)", Card::new(Suit::Red, Rank::Five), &[Clue::Rank(Rank::Five)]);
+    assert!(card.touched());
+    assert_eq!(card.possible_values(), ranks(&[Rank::Five]));
+    assert!(!card.possible_colors().contains(Suit::Brown));
+
+    let card = clued_card("Brown (6 Suits)", Card::new(Suit::Brown, Rank::Two), &[Clue::Suit(Suit::Brown)]);
+    assert_eq!(card.possible_colors(), suits(&[Suit::Brown]));
+}
+
+#[test]
+fn light_pink_is_touched_by_ranks_but_not_colors() {
+    let variant = registry::Registry::bundled().by_name("Light Pink (6 Suits)").unwrap();
+    assert!(!variant.clue_colors().contains(&Suit::LightPink));
+
+    // Missing every color leaves light pink as the only suit.
+    let colors = [Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple].map(Clue::Suit);
+    let card = clued_card("Light Pink (6 Suits)", Card::new(Suit::LightPink, Rank::Two), &colors);
+    assert!(!card.touched());
+    assert_eq!(card.possible_colors(), suits(&[Suit::LightPink]));
+
+    // Then a rank clue touches it without saying anything about its rank.
+    let clues = [&colors[..], &[Clue::Rank(Rank::Four)]].concat();
+    let card = clued_card("Light Pink (6 Suits)", Card::new(Suit::LightPink, Rank::Two), &clues);
+    assert!(card.touched());
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    // A color clue that touches a card rules light pink out.
+    let card = clued_card("Light Pink (6 Suits)", Card::new(Suit::Red, Rank::Two), &[Clue::Suit(Suit::Red)]);
+    assert_eq!(card.possible_colors(), suits(&[Suit::Red]));
+}
+
+#[test]
+fn cocoa_rainbow_is_touched_by_every_color_and_no_rank() {
+    let variant = registry::Registry::bundled().by_name("Cocoa Rainbow (6 Suits)").unwrap();
+    let deck = variant.starting_deck();
+    assert_eq!(deck.len(), 55);
+    assert_eq!(deck.iter().filter(|card| card.suit() == Suit::CocoaRainbow).count(), 5);
+
+    let card = clued_card("Cocoa Rainbow (6 Suits)", Card::new(Suit::CocoaRainbow, Rank::Three), &[Clue::Suit(Suit::Blue)]);
+    assert!(card.touched());
+    assert_eq!(card.possible_colors(), suits(&[Suit::Blue, Suit::CocoaRainbow]));
+
+    let card = clued_card(
+        "Cocoa Rainbow (6 Suits)",
+        Card::new(Suit::CocoaRainbow, Rank::Three),
+        &[Clue::Suit(Suit::Blue), Clue::Suit(Suit::Red), Clue::Rank(Rank::Three)],
+    );
+    assert_eq!(card.possible_colors(), suits(&[Suit::CocoaRainbow]));
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    let card = clued_card("Cocoa Rainbow (6 Suits)", Card::new(Suit::Blue, Rank::Three), &[Clue::Suit(Suit::Red)]);
+    assert!(!card.touched());
+    assert!(!card.possible_colors().contains(Suit::CocoaRainbow));
+}
+
+#[test]
+fn white_is_touched_by_no_color() {
+    let variant = registry::Registry::bundled().by_name("White (6 Suits)").unwrap();
+    assert!(!variant.clue_colors().contains(&Suit::White));
+
+    let card = clued_card("White (6 Suits)", Card::new(Suit::White, Rank::Three), &[Clue::Suit(Suit::Red)]);
+    assert!(!card.touched());
+    assert!(card.possible_colors().contains(Suit::White));
+    assert!(!card.possible_colors().contains(Suit::Red));
+
+    // Rank clues touch white cards as usual.
+    let card = clued_card("White (6 Suits)", Card::new(Suit::White, Rank::Three), &[Clue::Rank(Rank::Three)]);
+    assert!(card.touched());
+    assert_eq!(card.possible_values(), ranks(&[Rank::Three]));
+
+    let card = clued_card("White (6 Suits)", Card::new(Suit::Red, Rank::Three), &[Clue::Suit(Suit::Red)]);
+    assert_eq!(card.possible_colors(), suits(&[Suit::Red]));
+}
+
+#[test]
+fn omni_is_touched_by_every_clue() {
+    let card = clued_card("Omni (6 Suits)", Card::new(Suit::Omni, Rank::Two), &[Clue::Suit(Suit::Red), Clue::Rank(Rank::Five)]);
+    assert!(card.touched());
+    assert_eq!(card.possible_colors(), suits(&[Suit::Red, Suit::Omni]));
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    // Missing any clue rules omni out.
+    let card = clued_card("Omni (6 Suits)", Card::new(Suit::Red, Rank::Two), &[Clue::Rank(Rank::Five)]);
+    assert!(!card.touched());
+    assert!(!card.possible_colors().contains(Suit::Omni));
+    assert_eq!(card.possible_values(), ranks(&[Rank::One, Rank::Two, Rank::Three, Rank::Four]));
+}
+
+#[test]
+fn null_is_touched_by_no_clue() {
+    let variant = registry::Registry::bundled().by_name("Null (6 Suits)").unwrap();
+    let card = Card::new(Suit::Null, Rank::One);
+    for &color in variant.clue_colors() {
+        assert!(!variant.clue_touches(card, Clue::Suit(color)));
+    }
+    for rank in BitFlags::<Rank>::default() {
+        assert!(!variant.clue_touches(card, Clue::Rank(rank)));
+    }
+
+    // Being touched at all rules null out.
+    let card = clued_card("Null (6 Suits)", Card::new(Suit::Red, Rank::One), &[Clue::Rank(Rank::One)]);
+    assert!(!card.possible_colors().contains(Suit::Null));
+    let card = clued_card("Null (6 Suits)", Card::new(Suit::Null, Rank::One), &[Clue::Rank(Rank::One)]);
+    assert!(card.possible_colors().contains(Suit::Null));
+    assert_eq!(card.possible_values(), BitFlags::default());
+}
+
+#[test]
+fn muddy_rainbow_is_touched_by_every_color_and_no_rank() {
+    let variant = registry::Registry::bundled().by_name("Muddy Rainbow (6 Suits)").unwrap();
+    assert_eq!(variant.starting_deck().len(), 60);
+    assert_eq!(variant.clue_colors(), [Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple]);
+
+    let card = clued_card(
+        "Muddy Rainbow (6 Suits)",
+        Card::new(Suit::MuddyRainbow, Rank::One),
+        &[Clue::Suit(Suit::Green), Clue::Rank(Rank::One)],
+    );
+    assert!(card.touched());
+    assert_eq!(card.possible_colors(), suits(&[Suit::Green, Suit::MuddyRainbow]));
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    let card = clued_card("Muddy Rainbow (6 Suits)", Card::new(Suit::Green, Rank::One), &[Clue::Suit(Suit::Red)]);
+    assert!(!card.possible_colors().contains(Suit::MuddyRainbow));
+}
+
+#[test]
+fn special_ranks_follow_their_own_rules() {
+    let red = Clue::Suit(Suit::Red);
+    let three = Clue::Rank(Rank::Three);
+    // Whether a red clue and a three clue touch a blue one, then a red one, in each family.
+    let families = [
+        ("Rainbow-Ones", [(true, false), (true, false)]),
+        ("Pink-Ones", [(false, true), (true, true)]),
+        ("White-Ones", [(false, false), (false, false)]),
+        ("Brown-Ones", [(false, false), (true, false)]),
+        ("Omni-Ones", [(true, true), (true, true)]),
+        ("Null-Ones", [(false, false), (false, false)]),
+        ("Muddy-Rainbow-Ones", [(true, false), (true, false)]),
+        ("Light-Pink-Ones", [(false, true), (false, true)]),
+    ];
+    for (family, expected) in families {
+        let variant = registry::Registry::bundled().by_name(&format!("{family} (6 Suits)")).unwrap();
+        for (suit, (by_red, by_three)) in [Suit::Blue, Suit::Red].into_iter().zip(expected) {
+            let one = Card::new(suit, Rank::One);
+            assert_eq!(variant.clue_touches(one, red), by_red, "{family} {one}");
+            assert_eq!(variant.clue_touches(one, three), by_three, "{family} {one}");
+        }
+        // Other ranks are clued as usual.
+        assert!(!variant.clue_touches(Card::new(Suit::Blue, Rank::Two), red), "{family}");
+        assert!(variant.clue_touches(Card::new(Suit::Blue, Rank::Three), three), "{family}");
+
+        let fives = registry::Registry::bundled().by_name(&format!("{} (5 Suits)", family.replace("Ones", "Fives"))).unwrap();
+        assert_eq!(fives.special_rank.unwrap().rank, Rank::Five);
+        assert_eq!(fives.clue_touches(Card::new(Suit::Blue, Rank::Five), red), expected[0].0, "{family}");
+    }
+}
+
+#[test]
+fn special_ranks_inform_inference() {
+    // A red clue touching a card in "Rainbow-Ones" means it is red or a one.
+    let card = clued_card("Rainbow-Ones (5 Suits)", Card::new(Suit::Blue, Rank::One), &[Clue::Suit(Suit::Red)]);
+    assert!(card.touched());
+    assert_eq!(card.possible_colors(), suits(&[Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple]));
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    // Missing the red clue too means it is not a one.
+    let card = clued_card("Rainbow-Ones (5 Suits)", Card::new(Suit::Blue, Rank::Two), &[Clue::Suit(Suit::Red)]);
+    assert!(!card.possible_colors().contains(Suit::Red));
+    assert_eq!(card.possible_values(), ranks(&[Rank::Two, Rank::Three, Rank::Four, Rank::Five]));
+
+    // A three clue touching a card in "Pink-Fives" means it is a three or a five.
+    let card = clued_card("Pink-Fives (5 Suits)", Card::new(Suit::Green, Rank::Five), &[Clue::Rank(Rank::Three)]);
+    assert_eq!(card.possible_values(), ranks(&[Rank::Three, Rank::Five]));
+
+    // A five clue touches no fives in "Brown-Fives", so missing one says nothing about rank five.
+    let card = clued_card("Brown-Fives (5 Suits)", Card::new(Suit::Green, Rank::Five), &[Clue::Rank(Rank::Five)]);
+    assert!(!card.touched());
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    // Missing a color clue in "White-Ones" rules out the color, except for the ones.
+    let card = clued_card("White-Ones (5 Suits)", Card::new(Suit::Red, Rank::One), &[Clue::Suit(Suit::Red)]);
+    assert!(!card.touched());
+    assert!(card.possible_colors().contains(Suit::Red));
+    let card = clued_card("White-Ones (5 Suits)", Card::new(Suit::Red, Rank::One), &[Clue::Rank(Rank::Two), Clue::Suit(Suit::Red)]);
+    assert!(card.possible_colors().contains(Suit::Red));
+    let card = clued_card("White-Ones (5 Suits)", Card::new(Suit::Blue, Rank::Two), &[Clue::Rank(Rank::Two), Clue::Suit(Suit::Red)]);
+    assert!(!card.possible_colors().contains(Suit::Red));
+}
+
+/// Start a game of a bundled variant where the first player is dealt `hand`.
+fn game_dealing(variant: &str, hand: [Card; 5]) -> HanabiGame {
+    let variant = registry::Registry::bundled().by_name(variant).unwrap();
+    let mut rest = variant.starting_deck();
+    for card in hand {
+        let index = rest.iter().position(|&c| c == card).unwrap();
+        rest.remove(index);
+    }
+    HanabiGame::with_deck(two_players(), variant, GameConfig::default(), [hand.to_vec(), rest].concat()).unwrap()
+}
+
+/// Have the second player clue `clue`, then the first player play `card`, wherever it is in hand.
+fn clue_then_play(game: &mut HanabiGame, clue: Clue, card: Card) -> Vec<GameEvent> {
+    game.act(AnnotatedAction { player: 1, action: Action::Clue { clue, target: 0 } }).unwrap();
+    let slot = game.players[0].hand.iter().position(|c| c.card == card).unwrap();
+    game.act(AnnotatedAction { player: 0, action: Action::Play(slot) }).unwrap()
+}
+
+#[test]
+fn up_or_down_deck_has_start_cards() {
+    let variant = registry::Registry::bundled().by_name("Up or Down (5 Suits)").unwrap();
+    let deck = variant.starting_deck();
+    assert_eq!(deck.len(), 45);
+    for suit in variant.suits() {
+        let count = |rank| deck.iter().filter(|&&card| card == Card::new(*suit, rank)).count();
+        assert_eq!([Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Start].map(count), [1, 2, 2, 2, 1, 1]);
+    }
+    assert!(!variant.legal_rank_clues().contains(Rank::Start));
+    assert_eq!(Rank::try_from("s"), Ok(Rank::Start));
+    assert_eq!(usize::from(Rank::Start), 7);
+}
+
+#[test]
+fn up_or_down_stacks_go_either_way() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let mut game = game_dealing("Up or Down (5 Suits)", [red(Rank::Five), red(Rank::Four), red(Rank::Three), red(Rank::Two), red(Rank::One)]);
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Undecided);
+    assert_eq!(game.playable_ranks(Suit::Red), Rank::One | Rank::Five | Rank::Start);
+
+    // Start at five and go down.
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Down);
+    assert!(!game.is_trash(red(Rank::One)));
+    assert!(game.is_trash(red(Rank::Start)));
+    for rank in [Rank::Four, Rank::Three, Rank::Two, Rank::One] {
+        clue_then_play(&mut game, Clue::Suit(Suit::Red), red(rank));
+    }
+    assert_eq!(game.stacks()[&Suit::Red].len(), 5);
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Finished);
+    assert_eq!(game.bombs_left, game.config().max_bombs);
+}
+
+#[test]
+fn up_or_down_start_cards() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let mut game = game_dealing("Up or Down (5 Suits)", [red(Rank::Start), red(Rank::Four), red(Rank::Two), red(Rank::One), red(Rank::Five)]);
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Undecided);
+    assert_eq!(game.playable_ranks(Suit::Red), Rank::Two | Rank::Four);
+    // With the START card down, the first one and five can only finish a stack.
+    assert!(!game.is_trash(red(Rank::One)));
+    assert!(!game.is_trash(red(Rank::Five)));
+    assert!(game.is_trash(red(Rank::Start)));
+
+    // Going down from START leaves the five behind.
+    clue_then_play(&mut game, Clue::Suit(Suit::Red), red(Rank::Four));
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Down);
+    assert_eq!(game.playable_ranks(Suit::Red), BitFlags::from(Rank::Three));
+    assert!(game.is_trash(red(Rank::Five)));
+    assert!(!game.is_trash(red(Rank::One)));
+
+    // A five can't be played on a stack going down.
+    let events = clue_then_play(&mut game, Clue::Suit(Suit::Red), red(Rank::Five));
+    assert!(events.iter().any(|event| matches!(event, GameEvent::Strike { .. })));
+}
+
+#[test]
+fn up_or_down_start_then_down() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let mut game = game_dealing("Up or Down (5 Suits)", [red(Rank::Start), red(Rank::Four), red(Rank::Three), red(Rank::Two), red(Rank::One)]);
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Undecided);
+    for rank in [Rank::Four, Rank::Three, Rank::Two] {
+        clue_then_play(&mut game, Clue::Suit(Suit::Red), red(rank));
+        assert_eq!(game.stack_direction(Suit::Red), StackDirection::Down);
+    }
+    clue_then_play(&mut game, Clue::Suit(Suit::Red), red(Rank::One));
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Finished);
+    assert_eq!(game.bombs_left, game.config().max_bombs);
+}
+
+#[test]
+fn up_or_down_max_score_and_critical_cards() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let game = game_dealing("Up or Down (5 Suits)", [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five].map(|rank| Card::new(Suit::Blue, rank)));
+    assert_eq!(game.max_score(), 25);
+    // A one or the START card can begin an upward stack, so neither is critical alone.
+    assert!(!game.is_critical(red(Rank::One)));
+    assert!(!game.is_critical(red(Rank::Start)));
+    assert!(!game.is_critical(red(Rank::Five)));
+    assert!(!game.is_critical(red(Rank::Three)));
+
+    // Without the one and the five, red can only get to four from the START card either way.
+    let mut lost = game.clone();
+    lost.discard_pile = vec![red(Rank::One), red(Rank::Five)];
+    assert_eq!(lost.max_score(), 24);
+    assert!(lost.is_critical(red(Rank::Start)));
+    lost.discard_pile.push(red(Rank::Start));
+    assert_eq!(lost.max_score(), 20);
+
+    // Losing both twos means the stack has to go down, where the twos are the fourth card.
+    let mut lost = game.clone();
+    lost.discard_pile = vec![red(Rank::Two), red(Rank::Two)];
+    assert_eq!(lost.max_score(), 23);
+    assert!(!lost.is_trash(red(Rank::One)));
+    assert!(!lost.is_critical(red(Rank::Five)));
+    assert!(!lost.is_critical(red(Rank::Start)));
+}
+
+#[test]
+fn reversed_variants_use_the_website_names() {
+    let registry = registry::Registry::bundled();
+    let teal = registry.by_name("Teal Reversed (6 Suits)").unwrap();
+    assert_eq!(teal.suits().last(), Some(&Suit::TealReversed));
+    let purple = registry.by_name("Purple Reversed (5 Suits)").unwrap();
+    assert_eq!(purple.suits().last(), Some(&Suit::PurpleReversed));
+    assert!(registry.by_name("Reversed (5 Suits)").is_none());
+}
+
+#[test]
+fn reversed_suits_go_down() {
+    let variant = registry::Registry::bundled().by_name("Purple Reversed (5 Suits)").unwrap();
+    let deck = variant.starting_deck();
+    let count = |rank| deck.iter().filter(|&&card| card == Card::new(Suit::PurpleReversed, rank)).count();
+    assert_eq!([Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five].map(count), [1, 2, 2, 2, 3]);
+    assert_eq!(variant.clue_colors(), [Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple]);
+
+    let reversed = |rank| Card::new(Suit::PurpleReversed, rank);
+    let mut game = game_dealing("Purple Reversed (5 Suits)", [reversed(Rank::Five), reversed(Rank::Four), reversed(Rank::Three), reversed(Rank::One), reversed(Rank::Two)]);
+    assert_eq!(game.stack_direction(Suit::PurpleReversed), StackDirection::Down);
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Up);
+    assert_eq!(game.playable_ranks(Suit::PurpleReversed), BitFlags::from(Rank::Five));
+    assert!(game.is_critical(reversed(Rank::One)));
+    assert!(!game.is_critical(reversed(Rank::Five)));
+
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert!(game.is_trash(reversed(Rank::Five)));
+    clue_then_play(&mut game, Clue::Suit(Suit::Purple), reversed(Rank::Four));
+    assert_eq!(game.stacks()[&Suit::PurpleReversed].len(), 2);
+
+    // The one comes last, so playing it now is a misplay.
+    let events = clue_then_play(&mut game, Clue::Suit(Suit::Purple), reversed(Rank::One));
+    assert!(events.iter().any(|event| matches!(event, GameEvent::Strike { .. })));
+}
+
+#[test]
+fn sudoku_stacks_start_anywhere() {
+    let variant = registry::Registry::bundled().by_name("Sudoku (5 Suits)").unwrap();
+    let deck = variant.starting_deck();
+    assert_eq!(deck.len(), 50);
+    assert_eq!(deck.iter().filter(|&&card| card == Card::new(Suit::Red, Rank::Five)).count(), 2);
+
+    let red = |rank| Card::new(Suit::Red, rank);
+    let mut game = game_dealing("Sudoku (5 Suits)", [red(Rank::Three), red(Rank::Four), red(Rank::Five), red(Rank::One), Card::new(Suit::Yellow, Rank::Three)]);
+    assert_eq!(game.playable_ranks(Suit::Red), BitFlags::<Rank>::default());
+
+    // Red starts at three and wraps around from five to one.
+    game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert_eq!(game.stack_direction(Suit::Red), StackDirection::Up);
+    assert_eq!(game.playable_ranks(Suit::Red), BitFlags::from(Rank::Four));
+    for rank in [Rank::Four, Rank::Five, Rank::One] {
+        clue_then_play(&mut game, Clue::Suit(Suit::Red), red(rank));
+    }
+    assert_eq!(game.playable_ranks(Suit::Red), BitFlags::from(Rank::Two));
+    assert!(game.is_trash(red(Rank::Three)));
+    assert!(!game.is_trash(red(Rank::Two)));
+
+    // No other stack can start at three.
+    assert_eq!(game.playable_ranks(Suit::Yellow), Rank::One | Rank::Two | Rank::Four | Rank::Five);
+    let events = clue_then_play(&mut game, Clue::Suit(Suit::Yellow), Card::new(Suit::Yellow, Rank::Three));
+    assert!(events.iter().any(|event| matches!(event, GameEvent::Strike { .. })));
+    assert_eq!(game.max_score(), 25);
+}
+
+#[test]
+fn throw_it_in_a_hole_hides_plays() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let mut game = game_dealing("Throw It in a Hole (5 Suits)", [red(Rank::One), red(Rank::Three), red(Rank::Two), red(Rank::Four), red(Rank::Five)]);
+    assert!(game.concealed());
+
+    let events = game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert!(events.contains(&GameEvent::Play { player: 0, order: 0, card: red(Rank::One) }));
+    let public = game.public_events(&events);
+    assert!(public.contains(&GameEvent::HiddenPlay { player: 0, order: 0 }));
+    assert!(!public.iter().any(|event| matches!(event, GameEvent::Play { .. } | GameEvent::Status { .. })));
+    assert!(public.iter().any(|event| matches!(event, GameEvent::HiddenDraw { .. })));
+
+    // A misplay looks the same, and the strike is kept quiet.
+    let events = clue_then_play(&mut game, Clue::Suit(Suit::Red), red(Rank::Three));
+    let public = game.public_events(&events);
+    assert!(public.contains(&GameEvent::HiddenPlay { player: 0, order: 1 }));
+    assert!(!public.iter().any(|event| matches!(event, GameEvent::Strike { .. } | GameEvent::Discard { .. })));
+
+    let view = game.view(1);
+    assert_eq!(view.hole, vec![0, 1]);
+    assert!(view.stacks.is_empty());
+    assert!(view.discard_pile.is_empty());
+    assert_eq!((view.score, view.max_score, view.bombs_left), (None, None, None));
+    assert_eq!(game.stacks_score(), 1);
+    assert_eq!(game.discard_pile, vec![red(Rank::Three)]);
+
+    // Striking out would give the misplays away, so the game carries on.
+    game.bombs_left = 1;
+    clue_then_play(&mut game, Clue::Suit(Suit::Red), red(Rank::Four));
+    assert_eq!(game.state, GameState::InProgress);
+    assert!(game.concealed());
+
+    // Everything comes out once the game is over, and the strikes cost the score.
+    game.turns_remaining = Some(0);
+    let events = game.act(AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Suit(Suit::Red), target: 0 } }).unwrap();
+    assert!(events.contains(&GameEvent::GameOver { end_condition: EndCondition::Strikeout }));
+    assert!(!game.concealed());
+    let view = game.view(1);
+    assert_eq!(view.score, Some(0));
+    assert_eq!(view.discard_pile, vec![red(Rank::Three), red(Rank::Four)]);
+    assert_eq!(view.stacks[&Suit::Red], vec![red(Rank::One)]);
+}
+
+#[test]
+fn hidden_plays_do_not_end_the_game_early() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let hand = [red(Rank::Five), red(Rank::One), red(Rank::Two), red(Rank::Three), red(Rank::Four)];
+    for (name, ends) in [("No Variant", true), ("Throw It in a Hole (5 Suits)", false)] {
+        let mut game = game_dealing(name, hand);
+        // Fill every stack but the last red card.
+        for &suit in game.variant.suits() {
+            let ranks = [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five];
+            let top = if suit == Suit::Red { 4 } else { 5 };
+            game.stacks.insert(suit, ranks[..top].iter().map(|&rank| Card::new(suit, rank)).collect());
+        }
+
+        game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+        assert_eq!(game.stacks_score(), game.max_score());
+        assert_eq!(game.state == GameState::Ended, ends, "{name}");
+    }
+}
+
+#[test]
+fn public_events_pass_through_normal_games() {
+    let mut game = two_player_game();
+    let events = game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    assert!(!game.concealed());
+    // Only the drawn card is kept back.
+    let hide_draws = |event: &GameEvent| match *event {
+        GameEvent::Draw { player, order, .. } => GameEvent::HiddenDraw { player, order },
+        ref event => event.clone(),
+    };
+    assert_eq!(game.public_events(&events), events.iter().map(hide_draws).collect::<Vec<_>>());
+}
+
+#[test]
+fn players_cannot_see_their_own_draws() {
+    let mut game = two_player_game();
+    let events = game.act(AnnotatedAction { player: 0, action: Action::Play(0) }).unwrap();
+    let drawn = events.iter()
+        .find_map(|event| match *event {
+            GameEvent::Draw { player: 0, order, card } => Some((order, card)),
+            _ => None,
+        })
+        .unwrap();
+
+    // The drawer only learns that a card was drawn, while the other player sees it.
+    let own = game.events_for(0, &events);
+    assert!(own.contains(&GameEvent::HiddenDraw { player: 0, order: drawn.0 }));
+    assert!(!own.iter().any(|event| matches!(event, GameEvent::Draw { .. })));
+    let other = game.events_for(1, &events);
+    assert!(other.contains(&GameEvent::Draw { player: 0, order: drawn.0, card: drawn.1 }));
+    assert!(!game.public_events(&events).iter().any(|event| matches!(event, GameEvent::Draw { .. })));
+}
+
+#[test]
+fn clue_starved_discards_give_half_a_clue() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let mut game = game_dealing("Clue Starved (5 Suits)", [red(Rank::One), red(Rank::Two), red(Rank::Three), red(Rank::Four), red(Rank::Five)]);
+    let clue = AnnotatedAction { player: 0, action: Action::Clue { clue: Clue::Suit(Suit::Red), target: 1 } };
+    let discard = AnnotatedAction { player: 1, action: Action::Discard(4) };
+
+    game.act(clue).unwrap();
+    game.act(discard).unwrap();
+    assert_eq!((game.clues, game.half_clue), (7, true));
+    assert!(game.view(0).half_clue);
+    game.act(clue).unwrap();
+    game.act(discard).unwrap();
+    assert_eq!((game.clues, game.half_clue), (7, false));
+
+    // The discards the pace allows and the fives still to come are worth half a clue each.
+    let regained = game.pace() as usize + 5;
+    assert_eq!(game.required_efficiency().clues, 7 + regained / 2);
+}
+
+#[test]
+fn blind_clues_touch_nothing() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let hand = [red(Rank::One), red(Rank::Two), red(Rank::Three), red(Rank::Four), red(Rank::Five)];
+
+    let mut game = game_dealing("Color Blind (5 Suits)", hand);
+    game.current_player = 1;
+    assert!(game.clue_touches(Clue::Suit(Suit::Red), 0).is_empty());
+    assert_eq!(game.clue_touches(Clue::Rank(Rank::One), 0), vec![0]);
+    // A color clue is always allowed, even though it touches nothing and tells nothing.
+    let events = game.act(AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Suit(Suit::Blue), target: 0 } }).unwrap();
+    assert!(events.iter().any(|event| matches!(event, GameEvent::Clue { list, .. } if list.is_empty())));
+    let card = &game.players[0].hand[0];
+    assert!(!card.touched());
+    assert_eq!(card.possible_colors(), BitFlags::default());
+    assert_eq!(card.possible_values(), BitFlags::default());
+
+    let game = game_dealing("Number Blind (5 Suits)", hand);
+    assert!(game.clue_touches(Clue::Rank(Rank::One), 0).is_empty());
+    assert_eq!(game.clue_touches(Clue::Suit(Suit::Red), 0).len(), 5);
+
+    let game = game_dealing("Totally Blind (5 Suits)", hand);
+    assert!(game.clue_touches(Clue::Rank(Rank::One), 0).is_empty());
+    assert!(game.clue_touches(Clue::Suit(Suit::Red), 0).is_empty());
+    assert!(game.variant().clue_touches_nothing(Clue::Rank(Rank::Five)));
+}
+
+#[test]
+fn cow_and_pig_clues_only_say_what_they_touched() {
+    let red = |rank| Card::new(Suit::Red, rank);
+    let hand = [red(Rank::One), Card::new(Suit::Blue, Rank::Two), red(Rank::Three), red(Rank::Four), red(Rank::Five)];
+    for (name, feedback) in [("Cow & Pig (5 Suits)", variants::ClueFeedback::KindOnly), ("Duck (5 Suits)", variants::ClueFeedback::Nothing)] {
+        let mut game = game_dealing(name, hand);
+        assert_eq!(game.variant().clue_feedback(), feedback);
+        game.current_player = 1;
+        let events = game.act(AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Suit(Suit::Red), target: 0 } }).unwrap();
+        assert!(events.iter().any(|event| matches!(event, GameEvent::Clue { list, .. } if list.len() == 4)));
+
+        // The touched cards are marked, but nothing is learned about what they are.
+        let hand = &game.players[0].hand;
+        assert!(hand[0].touched());
+        assert!(!hand[1].touched());
+        for card in hand {
+            assert_eq!(card.possible_colors(), BitFlags::default(), "{name}");
+            assert_eq!(card.possible_values(), BitFlags::default(), "{name}");
+        }
+        let view = game.view(0);
+        assert!(view.my_hand()[0].touched);
+        assert!(view.my_hand()[0].clues.is_empty());
+    }
+}
+
+#[test]
+fn other_seats_cannot_recover_hidden_clue_values() {
+    let hand = [Card::new(Suit::Red, Rank::One), Card::new(Suit::Yellow, Rank::Two), Card::new(Suit::Green, Rank::Three), Card::new(Suit::Blue, Rank::Four), Card::new(Suit::Purple, Rank::Five)];
+    // Have the second player clue the first, and collect what each seat sees of it.
+    let give = |name, clue| {
+        let mut game = game_dealing(name, hand);
+        game.current_player = 1;
+        let events = game.act(AnnotatedAction { player: 1, action: Action::Clue { clue, target: 0 } }).unwrap();
+        (game.public_events(&events), game.view(0).history, game.view(1).history)
+    };
+
+    // In "Duck", a red clue and a one clue touching the same card look exactly alike.
+    let red = give("Duck (5 Suits)", Clue::Suit(Suit::Red));
+    assert_eq!(red, give("Duck (5 Suits)", Clue::Rank(Rank::One)));
+    assert!(red.0.iter().any(|event| matches!(event, GameEvent::Clue { clue: Clue::Hidden(None), list, .. } if list == &[0])));
+    assert_eq!(red.1, vec![AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Hidden(None), target: 0 } }]);
+
+    // "Cow & Pig" tells color clues from rank clues, but not which color or rank.
+    let (events, history, _) = give("Cow & Pig (5 Suits)", Clue::Suit(Suit::Red));
+    let (blue_events, _, _) = give("Cow & Pig (5 Suits)", Clue::Suit(Suit::Blue));
+    let hidden_color = Clue::Hidden(Some(ClueKind::Color));
+    assert!(events.iter().any(|event| matches!(event, GameEvent::Clue { clue, .. } if *clue == hidden_color)));
+    assert!(blue_events.iter().any(|event| matches!(event, GameEvent::Clue { clue, .. } if *clue == hidden_color)));
+    assert_eq!(history[0].action, Action::Clue { clue: hidden_color, target: 0 });
+    let (_, history, _) = give("Cow & Pig (5 Suits)", Clue::Rank(Rank::Four));
+    assert_eq!(history[0].action, Action::Clue { clue: Clue::Hidden(Some(ClueKind::Rank)), target: 0 });
+
+    // What the players see can't be given as a clue.
+    let mut game = game_dealing("Duck (5 Suits)", hand);
+    game.current_player = 1;
+    let action = Action::Clue { clue: Clue::Hidden(None), target: 0 };
+    assert_eq!(game.check_action(AnnotatedAction { player: 1, action }), Err(ActError::IllegalClue));
+}
+
+#[test]
+fn dual_color_suits_are_touched_by_two_colors() {
+    let variant = registry::Registry::bundled().by_name("Dual-Color (3 Suits)").unwrap();
+    assert_eq!(variant.clue_colors(), [Suit::Red, Suit::Yellow, Suit::Blue]);
+    assert_eq!(variant.starting_deck().len(), 30);
+    assert!(variant.clue_touches(Card::new(Suit::OrangeD, Rank::One), Clue::Suit(Suit::Red)));
+    assert!(variant.clue_touches(Card::new(Suit::OrangeD, Rank::One), Clue::Suit(Suit::Yellow)));
+    assert!(!variant.clue_touches(Card::new(Suit::OrangeD, Rank::One), Clue::Suit(Suit::Blue)));
+
+    let orange = Card::new(Suit::OrangeD, Rank::Two);
+    let card = clued_card("Dual-Color (3 Suits)", orange, &[Clue::Suit(Suit::Red)]);
+    assert_eq!(card.possible_colors(), suits(&[Suit::OrangeD, Suit::PurpleD]));
+    let card = clued_card("Dual-Color (3 Suits)", orange, &[Clue::Suit(Suit::Red), Clue::Suit(Suit::Yellow)]);
+    assert_eq!(card.possible_colors(), suits(&[Suit::OrangeD]));
+    let card = clued_card("Dual-Color (3 Suits)", orange, &[Clue::Suit(Suit::Blue)]);
+    assert_eq!(card.possible_colors(), suits(&[Suit::OrangeD]));
+}
+
+#[test]
+fn ambiguous_suits_share_colors() {
+    let variant = registry::Registry::bundled().by_name("Ambiguous (6 Suits)").unwrap();
+    assert_eq!(variant.clue_colors(), [Suit::Red, Suit::Blue, Suit::Green]);
+    assert_eq!(variant.starting_deck().len(), 60);
+
+    let card = clued_card("Ambiguous (6 Suits)", Card::new(Suit::Tomato, Rank::Four), &[Clue::Suit(Suit::Red)]);
+    assert!(card.touched());
+    assert_eq!(card.possible_colors(), suits(&[Suit::Tomato, Suit::Mahogany]));
+    let card = clued_card("Ambiguous (6 Suits)", Card::new(Suit::Sky, Rank::Four), &[Clue::Suit(Suit::Red), Clue::Suit(Suit::Green)]);
+    assert!(!card.touched());
+    assert_eq!(card.possible_colors(), suits(&[Suit::Sky, Suit::Navy]));
+
+    // Suits keep their "Hanab Live" names when saved.
+    assert_eq!(serde_json::to_string(&Suit::OrangeD).unwrap(), "\"Orange D\"");
+    assert_eq!(Suit::try_from("ma"), Ok(Suit::Mahogany));
+}
+
+#[test]
+fn rank_special_decks() {
+    let registry = registry::Registry::bundled();
+    for name in ["Pink (6 Suits)", "Brown (6 Suits)", "Light Pink (6 Suits)"] {
+        let deck = registry.by_name(name).unwrap().starting_deck();
+        assert_eq!(deck.len(), 60, "{name}");
+    }
+    assert_eq!(registry.by_name("Pink (5 Suits)").unwrap().starting_deck().len(), 50);
+    assert_eq!(registry.by_name("Brown (6 Suits)").unwrap().clue_colors().last(), Some(&Suit::Brown));
+
+    // Clues in a game follow the suit rules too.
+    let deck = vec![
+        Card::new(Suit::Pink, Rank::One),
+        Card::new(Suit::Red, Rank::Two),
+        Card::new(Suit::Red, Rank::One),
+        Card::new(Suit::Blue, Rank::Three),
+        Card::new(Suit::Green, Rank::Four),
+        Card::new(Suit::Red, Rank::Three),
+        Card::new(Suit::Yellow, Rank::Two),
+        Card::new(Suit::Green, Rank::One),
+        Card::new(Suit::Blue, Rank::Four),
+        Card::new(Suit::Purple, Rank::Five),
+    ];
+    let game = HanabiGame::with_deck(two_players(), registry.by_name("Pink (6 Suits)").unwrap(), GameConfig::default(), deck).unwrap();
+    assert_eq!(game.clue_touches(Clue::Rank(Rank::Two), 0), vec![0, 1]);
+}
+
+#[test]
+fn critical_fours_have_one_copy() {
+    let variant = registry::Registry::bundled().by_name("Critical Fours (5 Suits)").unwrap();
+    let deck = variant.starting_deck();
+    assert_eq!(deck.len(), 45);
+    let count = |rank| deck.iter().filter(|&&card| card == Card::new(Suit::Red, rank)).count();
+    assert_eq!([Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five].map(count), [3, 2, 2, 1, 1]);
+
+    let game = game_dealing("Critical Fours (5 Suits)", [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five].map(|rank| Card::new(Suit::Blue, rank)));
+    assert!(game.is_critical(Card::new(Suit::Red, Rank::Four)));
+    assert!(!game.is_critical(Card::new(Suit::Red, Rank::Three)));
+}
+
+#[test]
+fn odds_and_evens_clue_parities() {
+    let variant = registry::Registry::bundled().by_name("Odds and Evens (5 Suits)").unwrap();
+    assert!(variant.legal_rank_clues().is_empty());
+    assert_eq!(&variant.clue_values()[5..], [Clue::Parity(Parity::Odd), Clue::Parity(Parity::Even)]);
+
+    let red = |rank| Card::new(Suit::Red, rank);
+    let mut game = game_dealing("Odds and Evens (5 Suits)", [red(Rank::One), red(Rank::Two), red(Rank::Three), red(Rank::Four), red(Rank::Five)]);
+    game.current_player = 1;
+    assert_eq!(game.clue_touches(Clue::Parity(Parity::Odd), 0), vec![0, 2, 4]);
+    let rank_clue = AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Rank(Rank::One), target: 0 } };
+    assert_eq!(game.check_action(rank_clue), Err(ActError::IllegalClue));
+    assert!(legal_actions(&game).contains(&Action::Clue { clue: Clue::Parity(Parity::Even), target: 0 }));
+
+    game.act(AnnotatedAction { player: 1, action: Action::Clue { clue: Clue::Parity(Parity::Even), target: 0 } }).unwrap();
+    let hand = &game.players[0].hand;
+    assert_eq!(hand[1].possible_values(), ranks(&[Rank::Two, Rank::Four]));
+    assert_eq!(hand[0].possible_values(), ranks(&[Rank::One, Rank::Three, Rank::Five]));
+
+    // Parity clues are saved by name.
+    assert_eq!(serde_json::to_string(&Clue::Parity(Parity::Odd)).unwrap(), "{\"Parity\":\"Odd\"}");
+}
+
+#[test]
+fn synesthesia_rank_clues_touch_a_color() {
+    let variant = registry::Registry::bundled().by_name("Synesthesia (5 Suits)").unwrap();
+    assert!(variant.clue_colors().is_empty());
+    assert_eq!(variant.clue_values(), [Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five].map(Clue::Rank));
+
+    // Ones also touch red, twos yellow, and so on.
+    let one = Clue::Rank(Rank::One);
+    assert!(variant.clue_touches(Card::new(Suit::Red, Rank::Four), one));
+    assert!(variant.clue_touches(Card::new(Suit::Blue, Rank::One), one));
+    assert!(!variant.clue_touches(Card::new(Suit::Yellow, Rank::Four), one));
+    assert!(variant.clue_touches(Card::new(Suit::Purple, Rank::Two), Clue::Rank(Rank::Five)));
+
+    let card = clued_card("Synesthesia (5 Suits)", Card::new(Suit::Green, Rank::Four), &[one]);
+    assert!(!card.touched());
+    assert!(!card.possible_colors().contains(Suit::Red));
+    assert!(!card.possible_values().contains(Rank::One));
+}
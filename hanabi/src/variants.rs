@@ -1,40 +1,217 @@
-use enumflags2::{BitFlag, BitFlags};
-use crate::cards::{AnnotatedCard, Card, Rank, Suit};
-
-pub trait Variant {
-    fn name() -> &'static str;
-    fn description() -> &'static str;
-    fn starting_deck(&self) -> Vec<AnnotatedCard>;
-    fn legal_suit_clues() -> BitFlags<Suit>;
-    fn legal_rank_clues() -> BitFlags<Rank>;
+use std::fmt::Debug;
+use enumflags2::{make_bitflags, BitFlags};
+use serde::{Deserialize, Serialize};
+use crate::cards::{Card, Rank, Suit};
+use crate::Clue;
+use crate::registry::Registry;
+
+/// The ranks that can fill each place of a stack, from the bottom up.
+pub type StackOrder = [BitFlags<Rank>; 5];
+
+/// The usual order, from 1 up to 5.
+pub const ASCENDING: StackOrder = [
+    make_bitflags!(Rank::{One}),
+    make_bitflags!(Rank::{Two}),
+    make_bitflags!(Rank::{Three}),
+    make_bitflags!(Rank::{Four}),
+    make_bitflags!(Rank::{Five}),
+];
+/// The order of reversed suits, from 5 down to 1.
+pub const DESCENDING: StackOrder = [
+    make_bitflags!(Rank::{Five}),
+    make_bitflags!(Rank::{Four}),
+    make_bitflags!(Rank::{Three}),
+    make_bitflags!(Rank::{Two}),
+    make_bitflags!(Rank::{One}),
+];
+
+/// How much the receiver of a clue learns besides which cards it touched.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ClueFeedback {
+    /// The color or rank of the clue, as usual.
+    Full,
+    /// Only whether it was a color or a rank clue, as in "Cow & Pig".
+    KindOnly,
+    /// Nothing more, as in "Duck".
+    Nothing,
+}
+
+/// The rules that change from one variant to another. Games keep their variant as a
+/// `dyn Variant`, and save it by name.
+pub trait Variant: Debug + Send + Sync {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    /// The suits in this variant, in the order they are displayed (and indexed by hanab.live).
+    fn suits(&self) -> &[Suit];
+    /// The colors that can be clued, in the order hanab.live numbers color clues.
+    fn clue_colors(&self) -> &[Suit];
+    fn starting_deck(&self) -> Vec<Card>;
+    fn legal_suit_clues(&self) -> BitFlags<Suit> {
+        self.clue_colors().iter().copied().collect()
+    }
+    fn legal_rank_clues(&self) -> BitFlags<Rank>;
+    /// Every clue that can be given in this variant: by default, each clue color followed by each
+    /// legal rank.
+    fn clue_values(&self) -> Vec<Clue> {
+        self.clue_colors().iter().map(|&suit| Clue::Suit(suit))
+            .chain(self.legal_rank_clues().iter().map(Clue::Rank))
+            .collect()
+    }
+    /// The orders the stack of `suit` may be built in. Which one a stack follows is settled by
+    /// the cards played on it.
+    fn stack_orders(&self, _suit: Suit) -> Vec<StackOrder> {
+        vec![ASCENDING]
+    }
+    /// Whether every stack has to begin with a different rank, as in "Sudoku".
+    fn unique_starts(&self) -> bool {
+        false
+    }
+    /// Whether clues like `clue` touch no cards at all, as color clues do in "Color Blind". Such
+    /// clues can always be given, even without [crate::GameConfig::empty_clues].
+    fn clue_touches_nothing(&self, _clue: Clue) -> bool {
+        false
+    }
+    /// How much the receiver of a clue learns about it.
+    fn clue_feedback(&self) -> ClueFeedback {
+        ClueFeedback::Full
+    }
+    /// Whether discards and finished stacks only give back half a clue, as in "Clue Starved".
+    fn clue_starved(&self) -> bool {
+        false
+    }
+    /// Whether played cards go face down, so that nobody learns what they were or whether they
+    /// scored until the game ends, as in "Throw It in a Hole".
+    fn hidden_plays(&self) -> bool {
+        false
+    }
+    /// Whether `clue` touches `card`. By default this follows the bundled suit definitions.
+    fn clue_touches(&self, card: Card, clue: Clue) -> bool {
+        match Registry::bundled().suit(card.suit()) {
+            Some(definition) => definition.touched_by(card, clue),
+            None => match clue {
+                Clue::Suit(color) => card.suit() == color,
+                Clue::Rank(rank) => card.rank() == rank,
+                Clue::Parity(parity) => parity.contains(card.rank()),
+                Clue::Hidden(_) => false,
+            },
+        }
+    }
+}
+
+impl<T: Variant + ?Sized> Variant for &T {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+
+    fn suits(&self) -> &[Suit] {
+        (**self).suits()
+    }
+
+    fn clue_colors(&self) -> &[Suit] {
+        (**self).clue_colors()
+    }
+
+    fn starting_deck(&self) -> Vec<Card> {
+        (**self).starting_deck()
+    }
+
+    fn legal_suit_clues(&self) -> BitFlags<Suit> {
+        (**self).legal_suit_clues()
+    }
+
+    fn legal_rank_clues(&self) -> BitFlags<Rank> {
+        (**self).legal_rank_clues()
+    }
+
+    fn clue_values(&self) -> Vec<Clue> {
+        (**self).clue_values()
+    }
+
+    fn stack_orders(&self, suit: Suit) -> Vec<StackOrder> {
+        (**self).stack_orders(suit)
+    }
+
+    fn unique_starts(&self) -> bool {
+        (**self).unique_starts()
+    }
+
+    fn clue_touches_nothing(&self, clue: Clue) -> bool {
+        (**self).clue_touches_nothing(clue)
+    }
+
+    fn clue_feedback(&self) -> ClueFeedback {
+        (**self).clue_feedback()
+    }
+
+    fn clue_starved(&self) -> bool {
+        (**self).clue_starved()
+    }
+
+    fn hidden_plays(&self) -> bool {
+        (**self).hidden_plays()
+    }
+
+    fn clue_touches(&self, card: Card, clue: Clue) -> bool {
+        (**self).clue_touches(card, clue)
+    }
+}
+
+/// Serialize a game's variant as its name, and look it up in the bundled
+/// [crate::registry::Registry] when loading it again.
+pub(crate) mod by_name {
+    use std::sync::Arc;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use crate::registry::Registry;
+    use super::Variant;
+
+    pub fn serialize<S: Serializer>(variant: &Arc<dyn Variant>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(variant.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<dyn Variant>, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        match Registry::bundled().by_name(&name) {
+            Some(variant) => Ok(Arc::new(variant.clone())),
+            None => Err(de::Error::custom(format!("unknown variant {:?}", name))),
+        }
+    }
 }
 
+#[derive(Debug)]
 pub struct NoVariant;
 impl Variant for NoVariant {
-    fn name() -> &'static str {
-        "No Variant (5 Suits)"
+    fn name(&self) -> &str {
+        "No Variant"
     }
 
-    fn description() -> &'static str {
+    fn description(&self) -> &str {
         "The original Hanabi rules."
     }
 
-    fn starting_deck(&self) -> Vec<AnnotatedCard> {
+    fn suits(&self) -> &[Suit] {
+        &[Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple]
+    }
+
+    fn clue_colors(&self) -> &[Suit] {
+        self.suits()
+    }
+
+    fn starting_deck(&self) -> Vec<Card> {
         let mut deck = Vec::new();
-        for suit in [Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple] {
+        for &suit in self.suits() {
             for rank in [Rank::One, Rank::One, Rank::One, Rank::Two, Rank::Two, Rank::Three, Rank::Three, Rank::Four, Rank::Four, Rank::Five] {
-                deck.push(AnnotatedCard::new(Card::new(suit, rank)));
+                deck.push(Card::new(suit, rank));
             }
         }
         deck
     }
 
-    fn legal_suit_clues() -> BitFlags<Suit> {
-        BitFlags::from(Suit::Red) | BitFlags::from(Suit::Yellow) | BitFlags::from(Suit::Green) | BitFlags::from(Suit::Blue) | BitFlags::from(Suit::Purple)
-    }
-
-    fn legal_rank_clues() -> BitFlags<Rank> {
-        Rank::all()
+    fn legal_rank_clues(&self) -> BitFlags<Rank> {
+        BitFlags::default()
     }
 }
 impl Default for NoVariant {
@@ -49,32 +226,37 @@ impl NoVariant {
     }
 }
 
+#[derive(Debug)]
 pub struct Rainbow6Variant;
 impl Variant for Rainbow6Variant {
-    fn name() -> &'static str {
+    fn name(&self) -> &str {
         "Rainbow (6 Suits)"
     }
 
-    fn description() -> &'static str {
+    fn description(&self) -> &str {
         "A 'rainbow' suit is added, which is clued by all color clues."
     }
 
-    fn starting_deck(&self) -> Vec<AnnotatedCard> {
+    fn suits(&self) -> &[Suit] {
+        &[Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple, Suit::Rainbow]
+    }
+
+    fn clue_colors(&self) -> &[Suit] {
+        &[Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple]
+    }
+
+    fn starting_deck(&self) -> Vec<Card> {
         let mut deck = Vec::new();
-        for suit in [Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple, Suit::Rainbow] {
+        for &suit in self.suits() {
             for rank in [Rank::One, Rank::One, Rank::One, Rank::Two, Rank::Two, Rank::Three, Rank::Three, Rank::Four, Rank::Four, Rank::Five] {
-                deck.push(AnnotatedCard::new(Card::new(suit, rank)));
+                deck.push(Card::new(suit, rank));
             }
         }
         deck
     }
 
-    fn legal_suit_clues() -> BitFlags<Suit> {
-        BitFlags::from(Suit::Red) | BitFlags::from(Suit::Yellow) | BitFlags::from(Suit::Green) | BitFlags::from(Suit::Blue) | BitFlags::from(Suit::Purple)
-    }
-
-    fn legal_rank_clues() -> BitFlags<Rank> {
-        Rank::all()
+    fn legal_rank_clues(&self) -> BitFlags<Rank> {
+        BitFlags::default()
     }
 }
 impl Default for Rainbow6Variant {
@@ -88,3 +270,51 @@ impl Rainbow6Variant {
         Self
     }
 }
+
+#[derive(Debug)]
+pub struct Black6Variant;
+impl Variant for Black6Variant {
+    fn name(&self) -> &str {
+        "Black (6 Suits)"
+    }
+
+    fn description(&self) -> &str {
+        "A 'black' suit is added, which is clued by black and has only one copy of each rank."
+    }
+
+    fn suits(&self) -> &[Suit] {
+        &[Suit::Red, Suit::Yellow, Suit::Green, Suit::Blue, Suit::Purple, Suit::Black]
+    }
+
+    fn clue_colors(&self) -> &[Suit] {
+        self.suits()
+    }
+
+    fn starting_deck(&self) -> Vec<Card> {
+        let mut deck = Vec::new();
+        for &suit in self.suits() {
+            let ranks: &[Rank] = if suit == Suit::Black {
+                &[Rank::One, Rank::Two, Rank::Three, Rank::Four, Rank::Five]
+            } else {
+                &[Rank::One, Rank::One, Rank::One, Rank::Two, Rank::Two, Rank::Three, Rank::Three, Rank::Four, Rank::Four, Rank::Five]
+            };
+            deck.extend(ranks.iter().map(|&rank| Card::new(suit, rank)));
+        }
+        deck
+    }
+
+    fn legal_rank_clues(&self) -> BitFlags<Rank> {
+        BitFlags::default()
+    }
+}
+impl Default for Black6Variant {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Black6Variant {
+    pub fn new() -> Self {
+        Self
+    }
+}
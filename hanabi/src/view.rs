@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use enumflags2::BitFlags;
+use serde::{Deserialize, Serialize};
+use crate::cards::{AnnotatedCard, Card, Rank, Suit};
+use crate::stats::Efficiency;
+use crate::variants::ClueFeedback;
+use crate::{AnnotatedAction, Clue, HanabiGame, StackDirection};
+
+/// A card as seen by one player. The identity of a card is only visible if it is in someone
+/// else's hand; otherwise the viewer only knows what the clues on it have revealed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CardView {
+    pub order: usize,
+    pub card: Option<Card>,
+    /// The clues given to this card's hand, left empty in variants where clues don't reveal
+    /// their value.
+    pub clues: Vec<Clue>,
+    pub touched: bool,
+    pub possible_colors: BitFlags<Suit>,
+    pub possible_values: BitFlags<Rank>,
+}
+
+impl CardView {
+    fn new(card: &AnnotatedCard, visible: bool, feedback: ClueFeedback) -> Self {
+        Self {
+            order: card.order(),
+            card: visible.then(|| card.card()),
+            clues: match feedback {
+                ClueFeedback::Full => card.clues().to_vec(),
+                _ => Vec::new(),
+            },
+            touched: card.touched(),
+            possible_colors: card.possible_colors(),
+            possible_values: card.possible_values(),
+        }
+    }
+}
+
+/// Everything a single player is allowed to know about a game of Hanabi. This is what bots and
+/// user interfaces should be built on, so that nobody can see their own cards.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerView {
+    /// The seat this view belongs to.
+    pub viewer: usize,
+    pub names: Vec<String>,
+    pub hands: Vec<Vec<CardView>>,
+    /// The variant's suits, in the order their stacks should be shown.
+    pub suits: Vec<Suit>,
+    /// The stacks, which are left empty while plays are hidden.
+    pub stacks: HashMap<Suit, Vec<Card>>,
+    /// Which way each of the variant's stacks is being built.
+    pub directions: HashMap<Suit, StackDirection>,
+    /// The orders of the cards played face down, in variants that hide plays.
+    pub hole: Vec<usize>,
+    /// The discarded cards, leaving out misplays while plays are hidden.
+    pub discard_pile: Vec<Card>,
+    pub deck_size: usize,
+    pub clues: usize,
+    /// Half a clue has been given back on top of `clues`, in "Clue Starved".
+    pub half_clue: bool,
+    /// Everything from here to `required_efficiency` depends on the outcome of plays, so it is
+    /// `None` while plays are hidden.
+    pub bombs_left: Option<usize>,
+    pub score: Option<usize>,
+    pub max_score: Option<usize>,
+    pub pace: Option<isize>,
+    pub efficiency: Option<Efficiency>,
+    pub required_efficiency: Option<Efficiency>,
+    pub current_player: usize,
+    pub history: Vec<AnnotatedAction>,
+}
+
+impl PlayerView {
+    /// Get the viewer's own hand.
+    pub fn my_hand(&self) -> &[CardView] {
+        &self.hands[self.viewer]
+    }
+
+    /// Whether it is the viewer's turn.
+    pub fn my_turn(&self) -> bool {
+        self.current_player == self.viewer
+    }
+}
+
+impl HanabiGame {
+    /// Get the game as seen from `viewer`'s seat, with the identities of their own cards hidden.
+    pub fn view(&self, viewer: usize) -> PlayerView {
+        let concealed = self.concealed();
+        let mut discard_pile = self.discard_pile.clone();
+        if concealed {
+            for &(order, _) in self.hole.iter().filter(|&&(_, scored)| !scored) {
+                let card = self.initial_deck[order];
+                if let Some(index) = discard_pile.iter().rposition(|&discarded| discarded == card) {
+                    discard_pile.remove(index);
+                }
+            }
+        }
+
+        PlayerView {
+            viewer,
+            names: self.players.iter().map(|player| player.name.clone()).collect(),
+            hands: self.players.iter()
+                .enumerate()
+                .map(|(seat, player)| {
+                    player.hand.iter()
+                        .map(|card| CardView::new(card, seat != viewer, self.variant.clue_feedback()))
+                        .collect()
+                })
+                .collect(),
+            suits: self.variant.suits().to_vec(),
+            stacks: if concealed { HashMap::new() } else { self.stacks.clone() },
+            directions: self.variant.suits().iter()
+                .filter(|_| !concealed)
+                .map(|&suit| (suit, self.stack_direction(suit)))
+                .collect(),
+            hole: self.hole.iter().map(|&(order, _)| order).collect(),
+            discard_pile,
+            deck_size: self.deck.len(),
+            clues: self.clues,
+            half_clue: self.half_clue,
+            bombs_left: (!concealed).then_some(self.bombs_left),
+            score: (!concealed).then(|| self.score()),
+            max_score: (!concealed).then(|| self.max_score()),
+            pace: (!concealed).then(|| self.pace()),
+            efficiency: (!concealed).then(|| self.efficiency()),
+            required_efficiency: (!concealed).then(|| self.required_efficiency()),
+            current_player: self.current_player,
+            history: self.public_history(),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Conversion between "Hanab Live" game JSON ([types::Game]) and the [hanabi] engine.
+
+use thiserror::Error;
+use hanabi::{ActError, Action, AnnotatedAction, Clue, DealError, GameConfig, HanabiGame, Player};
+use hanabi::cards::{Card, Parity, Rank, Suit};
+use hanabi::registry::{Registry, VariantDefinition};
+use hanabi::variants::Variant;
+use crate::messages::notifications::{GameOptions, TableData};
+use crate::types;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ImportError {
+    #[error("card {index} of the deck does not exist in this variant")]
+    InvalidCard { index: usize },
+    #[error("turn {turn}: card {order} is not in the current player's hand")]
+    CardNotInHand { turn: usize, order: u64 },
+    #[error("turn {turn}: clue value {value} does not exist in this variant")]
+    InvalidClue { turn: usize, value: u64 },
+    #[error("turn {turn}: {source}")]
+    IllegalAction { turn: usize, source: ActError },
+    #[error(transparent)]
+    Deal(#[from] DealError),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ExportError {
+    #[error("card {0} does not exist in this variant")]
+    InvalidCard(Card),
+    #[error("turn {turn}: clue {clue:?} cannot be given in this variant")]
+    InvalidClue { turn: usize, clue: Clue },
+}
+
+impl types::Card {
+    /// Convert an engine card, looking its suit up in the variant's suit order.
+    pub fn from_card(card: Card, suits: &[Suit]) -> Option<Self> {
+        let suit_index = suits.iter().position(|&suit| suit == card.suit())?;
+        Some(Self {
+            suit_index: suit_index as u64,
+            rank: usize::from(card.rank()) as u64,
+        })
+    }
+
+    /// Convert this card to an engine card, looking its suit up in the variant's suit order.
+    pub fn to_card(&self, suits: &[Suit]) -> Option<Card> {
+        let suit = *suits.get(self.suit_index as usize)?;
+        let rank = Rank::try_from(self.rank as usize).ok()?;
+        Some(Card::new(suit, rank))
+    }
+}
+
+impl TableData {
+    /// Look up this table's variant in the bundled registry.
+    pub fn variant(&self) -> Option<&'static VariantDefinition> {
+        Registry::bundled().by_name(&self.variant)
+    }
+}
+
+impl types::Options {
+    /// Look up the game's variant in the bundled registry.
+    pub fn variant(&self) -> Option<&'static VariantDefinition> {
+        Registry::bundled().by_name(&self.variant)
+    }
+
+    /// Get the engine rules for a table with these options.
+    pub fn to_config(&self) -> GameConfig {
+        GameConfig {
+            empty_clues: self.empty_clues,
+            deck_plays: self.deck_plays,
+            one_extra_card: self.one_extra_card,
+            one_less_card: self.one_less_card,
+            all_or_nothing: self.all_or_nothing,
+            ..GameConfig::default()
+        }
+    }
+
+    /// Get the options for a table of the named variant played with the given engine rules.
+    pub fn from_config(variant: &str, config: GameConfig) -> Self {
+        Self {
+            variant: variant.to_string(),
+            speedrun: false,
+            card_cycle: false,
+            deck_plays: config.deck_plays,
+            empty_clues: config.empty_clues,
+            one_extra_card: config.one_extra_card,
+            one_less_card: config.one_less_card,
+            all_or_nothing: config.all_or_nothing,
+            detrimental_characters: false,
+        }
+    }
+}
+
+impl GameOptions {
+    /// Look up this table's variant in the bundled registry.
+    pub fn variant(&self) -> Option<&'static VariantDefinition> {
+        Registry::bundled().by_name(&self.variant_name)
+    }
+
+    /// Get the engine rules for a table with these options.
+    pub fn to_config(&self) -> GameConfig {
+        GameConfig {
+            empty_clues: self.empty_clues,
+            deck_plays: self.deck_plays,
+            one_extra_card: self.one_extra_card,
+            one_less_card: self.one_less_card,
+            all_or_nothing: self.all_or_nothing,
+            ..GameConfig::default()
+        }
+    }
+}
+
+/// Build a [HanabiGame] from a "Hanab Live" game, dealing its deck and replaying every action
+/// through [HanabiGame::act]. Replaying stops at the first `EndGame` action.
+///
+/// Errors report the 1-based turn number of the first action that could not be replayed, matching
+/// the turn numbers shown in the "Hanab Live" replay viewer.
+pub fn import_game(game: &types::Game, variant: impl Variant + 'static) -> Result<HanabiGame, ImportError> {
+    let deck = game.deck.iter()
+        .enumerate()
+        .map(|(index, card)| card.to_card(variant.suits()).ok_or(ImportError::InvalidCard { index }))
+        .collect::<Result<Vec<_>, _>>()?;
+    let players = game.players.iter().map(|name| Player::new(name.clone())).collect();
+    let config = game.options.as_ref().map(types::Options::to_config).unwrap_or_default();
+    let last_order = deck.len().saturating_sub(1);
+    let mut hanabi_game = HanabiGame::with_deck(players, variant, config, deck)?;
+
+    for (index, action) in game.actions.iter().enumerate() {
+        let turn = index + 1;
+        let player = hanabi_game.current_player();
+        let slot = |order: u64| match hanabi_game.find_card(order as usize) {
+            Some((holder, slot)) if holder == player => Ok(slot),
+            _ => Err(ImportError::CardNotInHand { turn, order }),
+        };
+
+        let action = match *action {
+            types::Action::Play { target }
+                if config.deck_plays && target as usize == last_order && hanabi_game.find_card(last_order).is_none() =>
+                Action::PlayDeck,
+            types::Action::Play { target } => Action::Play(slot(target)?),
+            types::Action::Discard { target } => Action::Discard(slot(target)?),
+            types::Action::ColorClue { target, value } => {
+                let suit = *hanabi_game.variant().clue_colors()
+                    .get(value as usize)
+                    .ok_or(ImportError::InvalidClue { turn, value })?;
+                Action::Clue { clue: Clue::Suit(suit), target: target as usize }
+            },
+            types::Action::RankClue { target, value } => {
+                // "Odds and Evens" numbers its two rank clues instead of using ranks.
+                let odds_and_evens = hanabi_game.variant().clue_values().contains(&Clue::Parity(Parity::Odd));
+                let clue = match odds_and_evens {
+                    true => Parity::try_from(value as usize).map(Clue::Parity),
+                    false => Rank::try_from(value as usize).map(Clue::Rank),
+                }.map_err(|_| ImportError::InvalidClue { turn, value })?;
+                Action::Clue { clue, target: target as usize }
+            },
+            types::Action::EndGame { .. } => break,
+        };
+
+        hanabi_game.act(AnnotatedAction { player, action })
+            .map_err(|source| ImportError::IllegalAction { turn, source })?;
+    }
+
+    Ok(hanabi_game)
+}
+
+/// Export a [HanabiGame] as a "Hanab Live" game, which can be uploaded with
+/// [crate::messages::commands::CommandReplayCreateData] and viewed in the website's replay viewer.
+///
+/// The engine does not keep per-card notes, so every player gets a blank note for each card in the
+/// deck, which is how the website lays out a game without notes.
+pub fn export_game(game: &HanabiGame) -> Result<types::Game, ExportError> {
+    let variant = game.variant();
+    let deck = game.initial_deck().iter()
+        .map(|&card| types::Card::from_card(card, variant.suits()).ok_or(ExportError::InvalidCard(card)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The history only records hand slots, so replay it from the start to recover card orders.
+    let mut replay = game.at_turn(0);
+
+    let mut actions = Vec::new();
+    for (index, &annotated_action) in game.history().iter().enumerate() {
+        let AnnotatedAction { player, action } = annotated_action;
+        let order = |slot: usize| replay.hand(player)[slot].order() as u64;
+        let action = match action {
+            Action::Play(slot) => types::Action::Play { target: order(slot) },
+            Action::Discard(slot) => types::Action::Discard { target: order(slot) },
+            // Only the last card of the deck can be played from it.
+            Action::PlayDeck => types::Action::Play { target: game.initial_deck().len() as u64 - 1 },
+            Action::Clue { clue: Clue::Suit(suit), target } => {
+                let value = variant.clue_colors().iter()
+                    .position(|&color| color == suit)
+                    .ok_or(ExportError::InvalidClue { turn: index + 1, clue: Clue::Suit(suit) })?;
+                types::Action::ColorClue { target: target as u64, value: value as u64 }
+            },
+            Action::Clue { clue: Clue::Rank(rank), target } => {
+                types::Action::RankClue { target: target as u64, value: usize::from(rank) as u64 }
+            },
+            Action::Clue { clue: Clue::Parity(parity), target } => {
+                types::Action::RankClue { target: target as u64, value: usize::from(parity) as u64 }
+            },
+            Action::Clue { clue: clue @ Clue::Hidden(_), .. } => {
+                return Err(ExportError::InvalidClue { turn: index + 1, clue });
+            },
+        };
+        actions.push(action);
+        replay.act(annotated_action).expect("history was legal when it was played");
+    }
+
+    let notes = vec![vec![String::new(); deck.len()]; game.players().len()];
+
+    Ok(types::Game {
+        id: None,
+        seed: None,
+        players: game.players().iter().map(|player| player.name.clone()).collect(),
+        deck,
+        actions,
+        options: Some(types::Options::from_config(variant.name(), game.config())),
+        notes: Some(notes),
+        characters: None,
+    })
+}
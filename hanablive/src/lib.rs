@@ -2,6 +2,8 @@ pub mod types;
 pub mod messages;
 pub mod errors;
 pub mod client;
+pub mod convert;
+pub mod seed;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -5,7 +5,9 @@ use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
 use crate::messages;
 use crate::messages::WebsocketParseError;
-use crate::types::Game;
+use hanabi::ActError;
+use crate::types::{Action, Card, Game};
+use crate::{convert, seed};
 
 #[test]
 fn example_game_parses() {
@@ -7463,4 +7465,160 @@ async fn websocket_trace_parses() -> Result<(), WebsocketParseError> {
     "#]];
     expected.assert_debug_eq(&trace);
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn crc64_matches_go() {
+    assert_eq!(seed::crc64(b"123456789"), 0x995DC9BBDF1939FA);
+}
+
+#[test]
+fn go_rand_matches_go() {
+    // rand.Seed(1); rand.Int63(); rand.Int63()
+    let mut rng = seed::GoRand::new(1);
+    assert_eq!(rng.int63(), 5577006791947779410);
+    assert_eq!(rng.int63(), 8674665223082153551);
+
+    // rand.Seed(1); rand.Intn(100) ten times
+    let mut rng = seed::GoRand::new(1);
+    let rolls: Vec<usize> = (0..10).map(|_| rng.intn(100)).collect();
+    assert_eq!(rolls, vec![81, 87, 47, 59, 81, 18, 25, 40, 56, 0]);
+}
+
+#[test]
+fn seeded_deck_is_a_shuffle() {
+    use hanabi::variants::{NoVariant, Variant};
+
+    let deck = seed::deck_from_seed(&seed::seed_name(3, 0, 12), &NoVariant);
+    assert_eq!(deck, seed::deck_from_seed("p3v0s12", &NoVariant));
+    assert_ne!(deck, seed::deck_from_seed("p3v0s13", &NoVariant));
+
+    let mut sorted = deck.clone();
+    sorted.sort();
+    let mut expected = NoVariant.starting_deck();
+    expected.sort();
+    assert_eq!(sorted, expected);
+
+    let deck: Vec<String> = deck.iter().map(ToString::to_string).collect();
+    let expected = expect!["g3 b5 b3 b1 p1 b1 b4 p2 g4 p1 b1 r3 y3 r1 g4 g1 y1 g5 y4 g1 r1 r2 g2 p2 r3 r1 p4 p3 r4 b2 b4 p3 y1 b3 b2 y1 g2 g3 y2 y5 y3 g1 y2 r4 y4 r2 r5 p4 p5 p1"];
+    expected.assert_eq(&deck.join(" "));
+}
+
+#[test]
+fn seeded_deck_matches_the_server() {
+    use hanabi::variants::{NoVariant, Variant};
+
+    // The trace records a game on seed `p2v193s1` (table 15029). "Matryoshka (5 Suits)" has the
+    // same deck as "No Variant". The server only told the recording player the cards the other
+    // player drew, so those are the cards that can be checked.
+    let data = include_str!("../test_data/ws_traces/simple_terminated_game.json");
+    let trace: Vec<Value> = serde_json::from_str(data).unwrap();
+    let mut revealed = Vec::new();
+    for entry in &trace {
+        let Some((command, body)) = entry["data"].as_str().unwrap().split_once(' ') else {
+            continue;
+        };
+        if command != "gameActionList" && command != "gameAction" {
+            continue;
+        }
+        let body: Value = serde_json::from_str(body).unwrap();
+        if body["tableID"] != 15029 {
+            continue;
+        }
+        let actions = match command {
+            "gameActionList" => body["list"].as_array().unwrap().clone(),
+            _ => vec![body["action"].clone()],
+        };
+        for action in actions {
+            if action["type"] == "draw" && action["suitIndex"].as_i64().unwrap() >= 0 {
+                revealed.push((action["order"].as_u64().unwrap() as usize, action["suitIndex"].as_u64().unwrap(), action["rank"].as_u64().unwrap()));
+            }
+        }
+    }
+    let orders: Vec<usize> = revealed.iter().map(|&(order, _, _)| order).collect();
+    assert_eq!(orders, vec![0, 1, 2, 3, 4, 10, 12, 15, 17]);
+
+    let deck = seed::deck_from_seed("p2v193s1", &NoVariant);
+    for (order, suit_index, rank) in revealed {
+        let card = Card::from_card(deck[order], NoVariant.suits()).unwrap();
+        assert_eq!((card.suit_index, card.rank), (suit_index, rank), "card {order}");
+    }
+}
+
+#[test]
+fn example_game_replays() {
+    use hanabi::variants::NoVariant;
+
+    let data = include_str!("../test_data/games/example_game.json");
+    let game: Game = serde_json::from_str(data).unwrap();
+    let result = convert::import_game(&game, &NoVariant).map(|game| (game.score(), game.history().len()));
+    let expected = expect![[r#"
+        Ok(
+            (
+                25,
+                55,
+            ),
+        )
+    "#]];
+    expected.assert_debug_eq(&result);
+}
+
+#[test]
+fn import_reports_first_illegal_action() {
+    use hanabi::variants::NoVariant;
+
+    let data = include_str!("../test_data/games/example_game.json");
+    let mut game: Game = serde_json::from_str(data).unwrap();
+    // Turn 3 is Cathy's; have her clue herself instead.
+    game.actions[2] = Action::RankClue { target: 2, value: 1 };
+    let result = convert::import_game(&game, &NoVariant).map(|game| game.score());
+    assert_eq!(result, Err(convert::ImportError::IllegalAction { turn: 3, source: ActError::CantClueYourself }));
+
+    game.actions[2] = Action::Play { target: 0 };
+    let result = convert::import_game(&game, &NoVariant).map(|game| game.score());
+    assert_eq!(result, Err(convert::ImportError::CardNotInHand { turn: 3, order: 0 }));
+}
+
+#[test]
+fn example_game_round_trips() {
+    use hanabi::variants::NoVariant;
+
+    let data = include_str!("../test_data/games/example_game.json");
+    let game: Game = serde_json::from_str(data).unwrap();
+    let exported = convert::export_game(&convert::import_game(&game, &NoVariant).unwrap()).unwrap();
+
+    assert_eq!(exported.players, game.players);
+    assert_eq!(serde_json::to_value(&exported.deck).unwrap(), serde_json::to_value(&game.deck).unwrap());
+    assert_eq!(serde_json::to_value(&exported.actions).unwrap(), serde_json::to_value(&game.actions).unwrap());
+    assert_eq!(exported.options.map(|options| options.variant), Some("No Variant".to_string()));
+    // One blank note per card, for each player.
+    let notes = exported.notes.unwrap();
+    assert_eq!(notes.len(), game.players.len());
+    assert!(notes.iter().all(|notes| notes.len() == game.deck.len() && notes.iter().all(String::is_empty)));
+}
+
+#[test]
+fn options_round_trip() {
+    use hanabi::variants::NoVariant;
+
+    let data = include_str!("../test_data/games/example_game.json");
+    let mut game: Game = serde_json::from_str(data).unwrap();
+    let options = game.options.get_or_insert_with(|| crate::types::Options::from_config("No Variant", Default::default()));
+    options.empty_clues = true;
+    options.deck_plays = true;
+
+    let imported = convert::import_game(&game, &NoVariant).unwrap();
+    assert!(imported.config().empty_clues && imported.config().deck_plays);
+    let exported = convert::export_game(&imported).unwrap().options.unwrap();
+    assert!(exported.empty_clues && exported.deck_plays && !exported.all_or_nothing);
+}
+
+#[test]
+fn options_name_a_variant() {
+    use hanabi::variants::Variant;
+
+    let data = include_str!("../test_data/games/example_game.json");
+    let game: Game = serde_json::from_str(data).unwrap();
+    let variant = game.options.as_ref().and_then(|options| options.variant()).unwrap();
+    assert_eq!(variant.name(), "No Variant");
+    assert!(convert::import_game(&game, variant).is_ok());
+}
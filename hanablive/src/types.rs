@@ -42,8 +42,8 @@ pub struct Game {
 pub struct Card {
     /// The suit number corresponds to the suits for the particular variant, from 0 to N. For
     /// example, 0 corresponds to red, 1 corresponds to yellow, and so on in a "No Variant" game.
-    suit_index: u64,
-    rank: u64,
+    pub suit_index: u64,
+    pub rank: u64,
 }
 
 #[derive(Debug, Clone, Copy)]